@@ -1,51 +1,40 @@
-fn parse_input<'a>(input: &'a str) -> impl Iterator<Item = u32> + 'a {
-    input.split("\n\n").map(|calorie_counts| {
+use common::parse::number;
+use common::{ParseError, Solution};
+
+#[derive(Default)]
+pub struct Day01;
+
+/// Counts are `u32` but their sums are kept as `u64`, so that no number of lines can overflow
+/// them.
+fn parse_input(input: &str) -> impl Iterator<Item = Result<u64, ParseError>> + '_ {
+    input.split("\n\n").map(move |calorie_counts| {
         calorie_counts
             .lines()
-            .map(|calories| calories.parse::<u32>().unwrap())
-            .sum::<u32>()
+            .map(|calories| number::<u32>(input, calories).map(u64::from))
+            .sum::<Result<u64, _>>()
     })
 }
 
-pub fn part_1(input: &str) -> String {
-    parse_input(input).max().unwrap().to_string()
-}
-
-pub fn part_2(input: &str) -> String {
-    let mut calorie_sums = parse_input(input).collect::<Vec<_>>();
-
-    calorie_sums.sort_by(|a, b| b.cmp(a));
-
-    calorie_sums.iter().take(3).sum::<u32>().to_string()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "\
-1000
-2000
-3000
+impl Solution for Day01 {
+    type Parsed<'a> = Vec<u64>;
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-4000
-
-5000
-6000
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input).collect()
+    }
 
-7000
-8000
-9000
+    fn part_1(&self, calorie_sums: &Vec<u64>) -> u64 {
+        *calorie_sums.iter().max().unwrap()
+    }
 
-10000";
+    fn part_2(&self, calorie_sums: &Vec<u64>) -> u64 {
+        let mut calorie_sums = calorie_sums.clone();
 
-    #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "24000");
-    }
+        calorie_sums.sort_by(|a, b| b.cmp(a));
 
-    #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "45000");
+        calorie_sums.iter().take(3).sum::<u64>()
     }
 }
+
+common::example_tests!(Day01, example: [part_1, part_2]);
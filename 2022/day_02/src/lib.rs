@@ -1,8 +1,37 @@
-pub fn part_1(input: &str) -> String {
+use common::{ParseError, Solution};
+
+#[derive(Default)]
+pub struct Day02;
+
+fn parse_round(input: &str, round: &str) -> Result<(char, char), ParseError> {
+    let theirs = match round.chars().next() {
+        Some(theirs @ 'A'..='C') => theirs,
+        _ => return Err(ParseError::at(input, round, "'A', 'B' or 'C'")),
+    };
+    if !round[1..].starts_with(' ') {
+        return Err(ParseError::at(input, &round[1..], "' '"));
+    }
+    let mine = match round[2..].chars().next() {
+        Some(mine @ 'X'..='Z') => mine,
+        _ => return Err(ParseError::at(input, &round[2..], "'X', 'Y' or 'Z'")),
+    };
+    if round.len() > 3 {
+        return Err(ParseError::at(input, &round[3..], "end of line"));
+    }
+    Ok((theirs, mine))
+}
+
+fn parse_rounds(input: &str) -> Result<Vec<(char, char)>, ParseError> {
     input
         .lines()
-        .map(|round| (round.chars().next().unwrap(), round.chars().nth(2).unwrap()))
-        .map(|(theirs, mine)| match theirs {
+        .map(|round| parse_round(input, round))
+        .collect()
+}
+
+fn score_part_1(rounds: &[(char, char)]) -> u32 {
+    rounds
+        .iter()
+        .map(|&(theirs, mine)| match theirs {
             'A' => match mine {
                 'X' => 4,
                 'Y' => 8,
@@ -24,14 +53,12 @@ pub fn part_1(input: &str) -> String {
             _ => 0,
         })
         .sum::<u32>()
-        .to_string()
 }
 
-pub fn part_2(input: &str) -> String {
-    input
-        .lines()
-        .map(|round| (round.chars().next().unwrap(), round.chars().nth(2).unwrap()))
-        .map(|(theirs, mine)| match theirs {
+fn score_part_2(rounds: &[(char, char)]) -> u32 {
+    rounds
+        .iter()
+        .map(|&(theirs, mine)| match theirs {
             'A' => match mine {
                 'X' => 3,
                 'Y' => 4,
@@ -53,24 +80,24 @@ pub fn part_2(input: &str) -> String {
             _ => 0,
         })
         .sum::<u32>()
-        .to_string()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl Solution for Day02 {
+    type Parsed<'a> = Vec<(char, char)>;
+    type Answer1 = u32;
+    type Answer2 = u32;
 
-    const INPUT: &str = "A Y
-B X
-C Z";
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_rounds(input)
+    }
 
-    #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "15");
+    fn part_1(&self, rounds: &Vec<(char, char)>) -> u32 {
+        score_part_1(rounds)
     }
 
-    #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "12");
+    fn part_2(&self, rounds: &Vec<(char, char)>) -> u32 {
+        score_part_2(rounds)
     }
 }
+
+common::example_tests!(Day02, example: [part_1, part_2]);
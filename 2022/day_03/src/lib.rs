@@ -2,6 +2,12 @@
 
 use std::collections::HashMap;
 
+use common::parse::all_chars;
+use common::{ParseError, Solution};
+
+#[derive(Default)]
+pub struct Day03;
+
 fn generate_char_priorities() -> HashMap<char, usize> {
     ('a'..='z')
         .chain('A'..='Z')
@@ -10,67 +16,83 @@ fn generate_char_priorities() -> HashMap<char, usize> {
         .collect::<HashMap<char, usize>>()
 }
 
-pub fn part_1(input: &str) -> String {
+/// The item type found in both compartments of `rucksack`.
+fn misplaced_item(rucksack: &str) -> Option<char> {
+    let (first_compartment, second_compartment) = rucksack.split_at(rucksack.len() / 2);
+    first_compartment
+        .chars()
+        .find(|letter| second_compartment.contains(*letter))
+}
+
+/// The item type carried by all three elves of a group.
+fn badge([first, second, third]: [&str; 3]) -> Option<char> {
+    first
+        .chars()
+        .find(|letter| second.contains(*letter) & third.contains(*letter))
+}
+
+fn sum_misplaced_priorities(rucksacks: &[&str]) -> usize {
     let char_priorities = generate_char_priorities();
 
-    input
-        .lines()
+    rucksacks
+        .iter()
         .map(|rucksack| {
-            let compartment_size = rucksack.len() / 2;
-            let first_compartment = &rucksack[0..compartment_size];
-            let second_compartment = &rucksack[compartment_size..rucksack.len()];
             char_priorities
-                .get(
-                    &(first_compartment
-                        .chars()
-                        .find(|letter| second_compartment.contains(*letter))
-                        .unwrap()),
-                )
+                .get(&misplaced_item(rucksack).unwrap())
                 .unwrap()
         })
         .sum::<usize>()
-        .to_string()
 }
 
-pub fn part_2(input: &str) -> String {
+fn sum_badge_priorities(rucksacks: &[&str]) -> usize {
     let char_priorities = generate_char_priorities();
-    input
-        .lines()
+    rucksacks
+        .iter()
+        .copied()
         .array_chunks::<3>()
-        .map(|[first, second, third]| {
-            char_priorities
-                .get(
-                    &(first
-                        .chars()
-                        .find(|letter| second.contains(*letter) & third.contains(*letter))
-                        .unwrap()),
-                )
-                .unwrap()
-        })
+        .map(|group| char_priorities.get(&badge(group).unwrap()).unwrap())
         .sum::<usize>()
-        .to_string()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl Solution for Day03 {
+    type Parsed<'a> = Vec<&'a str>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    const INPUT: &str = "\
-vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw\
-";
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        let rucksacks = input
+            .lines()
+            .map(|rucksack| {
+                all_chars(input, rucksack, "an item letter", |ch| {
+                    ch.is_ascii_alphabetic()
+                })?;
+                if rucksack.len() % 2 != 0 {
+                    let end = &rucksack[rucksack.len()..];
+                    return Err(ParseError::at(input, end, "an even number of items"));
+                }
+                if misplaced_item(rucksack).is_none() {
+                    let expected = "an item type in both compartments";
+                    return Err(ParseError::at(input, rucksack, expected));
+                }
+                Ok(rucksack)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for group in rucksacks.chunks_exact(3) {
+            if badge([group[0], group[1], group[2]]).is_none() {
+                let expected = "an item type shared with the two rucksacks before";
+                return Err(ParseError::at(input, group[2], expected));
+            }
+        }
+        Ok(rucksacks)
+    }
 
-    #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "157");
+    fn part_1(&self, rucksacks: &Vec<&str>) -> usize {
+        sum_misplaced_priorities(rucksacks)
     }
 
-    #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "70");
+    fn part_2(&self, rucksacks: &Vec<&str>) -> usize {
+        sum_badge_priorities(rucksacks)
     }
 }
+
+common::example_tests!(Day03, example: [part_1, part_2]);
@@ -1,52 +1,51 @@
-fn parse_starts_and_ends(input: &str) -> impl Iterator<Item = Vec<Vec<u32>>> + '_ {
-    input.lines().map(|line| {
-        line.split(',')
-            .map(|elf| {
-                elf.split('-')
-                    .map(|id| id.parse::<u32>().unwrap())
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<Vec<_>>>()
-    })
-}
+use common::parse::{number, split_once};
+use common::{ParseError, Solution};
 
-pub fn part_1(input: &str) -> String {
-    parse_starts_and_ends(input)
-        .filter(|line| {
-            (line[0][0] <= line[1][0] && line[0][1] >= line[1][1])
-                || (line[0][0] >= line[1][0] && line[0][1] <= line[1][1])
-        })
-        .count()
-        .to_string()
+#[derive(Default)]
+pub struct Day04;
+
+fn parse_range(input: &str, elf: &str) -> Result<Vec<u32>, ParseError> {
+    let (start, end) = split_once(input, elf, "-")?;
+    Ok(vec![number(input, start)?, number(input, end)?])
 }
 
-pub fn part_2(input: &str) -> String {
-    parse_starts_and_ends(input)
-        .filter(|line| !(line[0][1] < line[1][0] || line[0][0] > line[1][1]))
-        .count()
-        .to_string()
+fn parse_starts_and_ends(
+    input: &str,
+) -> impl Iterator<Item = Result<Vec<Vec<u32>>, ParseError>> + '_ {
+    input.lines().map(move |line| {
+        let (first, second) = split_once(input, line, ",")?;
+        Ok(vec![
+            parse_range(input, first)?,
+            parse_range(input, second)?,
+        ])
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "\
-2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8
-";
-
-    #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "2");
+impl Solution for Day04 {
+    type Parsed<'a> = Vec<Vec<Vec<u32>>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_starts_and_ends(input).collect()
     }
 
-    #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "4");
+    fn part_1(&self, pairs: &Vec<Vec<Vec<u32>>>) -> usize {
+        pairs
+            .iter()
+            .filter(|line| {
+                (line[0][0] <= line[1][0] && line[0][1] >= line[1][1])
+                    || (line[0][0] >= line[1][0] && line[0][1] <= line[1][1])
+            })
+            .count()
+    }
+
+    fn part_2(&self, pairs: &Vec<Vec<Vec<u32>>>) -> usize {
+        pairs
+            .iter()
+            .filter(|line| !(line[0][1] < line[1][0] || line[0][0] > line[1][1]))
+            .count()
     }
 }
+
+common::example_tests!(Day04, example: [part_1, part_2]);
@@ -1,19 +1,53 @@
+use std::str::FromStr;
+
+use common::nom::{finish, separated_to_end, tag, Error, IResult};
+use common::{ParseError, Solution};
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::{self, alpha1, digit1, multispace1, newline},
     multi::{many1, separated_list1},
     sequence::{delimited, preceded},
-    IResult,
 };
 
-struct Move {
+#[derive(Default)]
+pub struct Day05;
+
+/// One step of the rearrangement procedure, e.g. `move 1 from 2 to 1`, with the stacks as
+/// zero-based indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Move {
     amount: usize,
     origin: usize,
     destination: usize,
 }
 
-fn parse_crate(input: &str) -> IResult<&str, Option<&str>> {
+impl Move {
+    /// How many crates are moved.
+    pub fn amount(&self) -> usize {
+        self.amount
+    }
+
+    /// Index of the stack the crates are taken from, one less than its number in the notes.
+    pub fn origin(&self) -> usize {
+        self.origin
+    }
+
+    /// Index of the stack the crates are put on, one less than its number in the notes.
+    pub fn destination(&self) -> usize {
+        self.destination
+    }
+}
+
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, ParseError> {
+        finish(line, parse_move(line))
+    }
+}
+
+fn parse_crate(input: &str) -> IResult<'_, Option<&str>> {
     let (input, crated) = alt((
         tag("   "),
         delimited(complete::char('['), alpha1, complete::char(']')),
@@ -27,25 +61,23 @@ fn parse_crate(input: &str) -> IResult<&str, Option<&str>> {
     Ok((input, result))
 }
 
-fn parse_row(input: &str) -> IResult<&str, Vec<Option<&str>>> {
+fn parse_row(input: &str) -> IResult<'_, Vec<Option<&str>>> {
     let (input, result) = separated_list1(tag(" "), parse_crate)(input)?;
     Ok((input, result))
 }
 
-fn parse_stacks(input: &str) -> IResult<&str, Vec<Vec<&str>>> {
+fn parse_stacks(input: &str) -> IResult<'_, Vec<Vec<&str>>> {
     let (input, stacks_horizontal) = separated_list1(newline, parse_row)(input)?;
     let (input, _) = newline(input)?;
     let (input, _) = many1(preceded(multispace1, digit1))(input)?;
     let (input, _) = multispace1(input)?;
 
-    let mut stacks_vertical = vec![];
-    for _ in 0..=stacks_horizontal.len() {
-        stacks_vertical.push(vec![]);
-    }
+    let width = stacks_horizontal.iter().map(Vec::len).max().unwrap_or(0);
+    let mut stacks_vertical = vec![vec![]; width];
     for vec in stacks_horizontal.iter().rev() {
         for (i, crated) in vec.iter().enumerate() {
             if crated.is_some() {
-                stacks_vertical[i].push(crated.unwrap().clone());
+                stacks_vertical[i].push(crated.unwrap());
             }
         }
     }
@@ -53,53 +85,65 @@ fn parse_stacks(input: &str) -> IResult<&str, Vec<Vec<&str>>> {
     Ok((input, stacks_vertical))
 }
 
-fn parse_move(input: &str) -> IResult<&str, Move> {
+/// Parses a one-based stack number into an index.
+fn parse_stack(input: &str) -> IResult<'_, usize> {
+    let (rest, number) = complete::u32(input)?;
+    match (number as usize).checked_sub(1) {
+        Some(index) => Ok((rest, index)),
+        None => Err(nom::Err::Failure(Error::new(
+            input,
+            "a stack number from 1",
+        ))),
+    }
+}
+
+fn parse_move(input: &str) -> IResult<'_, Move> {
     let (input, _) = tag("move ")(input)?;
     let (input, amount) = complete::u32(input)?;
     let (input, _) = tag(" from ")(input)?;
-    let (input, origin) = complete::u32(input)?;
+    let (input, origin) = parse_stack(input)?;
     let (input, _) = tag(" to ")(input)?;
-    let (input, destination) = complete::u32(input)?;
+    let (input, destination) = parse_stack(input)?;
 
     Ok((
         input,
         Move {
             amount: amount as usize,
-            origin: (origin - 1) as usize,
-            destination: (destination - 1) as usize,
+            origin,
+            destination,
         },
     ))
 }
 
-fn parse_moves(input: &str) -> IResult<&str, Vec<Move>> {
-    let (input, result) = separated_list1(newline, parse_move)(input)?;
+/// Parses the moves, following the height of every stack so that a move from or to a missing
+/// stack, or of more crates than its origin holds, is reported here instead of panicking later.
+fn parse_moves<'a>(input: &'a str, stacks: &[Vec<&str>]) -> IResult<'a, Vec<Move>> {
+    let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+    let (input, result) = separated_to_end("\n", |line| {
+        let (rest, step) = parse_move(line)?;
+        let fail = |expected: String| Err(nom::Err::Failure(Error::new(line, expected)));
+        if step.origin >= heights.len() || step.destination >= heights.len() {
+            return fail(format!("a move between stacks 1 to {}", heights.len()));
+        }
+        if step.amount > heights[step.origin] {
+            return fail(format!("a move of at most {} crates", heights[step.origin]));
+        }
+        heights[step.origin] -= step.amount;
+        heights[step.destination] += step.amount;
+        Ok((rest, step))
+    })(input)?;
 
     Ok((input, result))
 }
 
-fn parse_input(input: &str) -> IResult<&str, (Vec<Vec<&str>>, Vec<Move>)> {
+fn parse_input(input: &str) -> IResult<'_, (Vec<Vec<&str>>, Vec<Move>)> {
     let (input, stacks) = parse_stacks(input)?;
-    let (input, moves) = parse_moves(input)?;
+    let (input, moves) = parse_moves(input, &stacks)?;
 
     Ok((input, (stacks, moves)))
 }
 
-pub fn part_1(input: &str) -> String {
-    let (_, (mut stacks, moves)) = parse_input(input).unwrap();
-    for Move {
-        amount,
-        origin,
-        destination,
-    } in moves.iter()
-    {
-        let origin_len = stacks[*origin].len();
-        let to_move = stacks[*origin]
-            .drain((origin_len - *amount)..)
-            .rev()
-            .collect::<Vec<_>>();
-        stacks[*destination].extend(to_move);
-    }
-
+fn top_crates(stacks: &[Vec<&str>]) -> String {
     stacks
         .iter()
         .map(|stack| match stack.iter().last() {
@@ -109,53 +153,74 @@ pub fn part_1(input: &str) -> String {
         .collect::<String>()
 }
 
-pub fn part_2(input: &str) -> String {
-    let (_, (mut stacks, moves)) = parse_input(input).unwrap();
-    for Move {
-        amount,
-        origin,
-        destination,
-    } in moves.iter()
-    {
-        let origin_len = stacks[*origin].len();
-        let to_move = stacks[*origin]
-            .drain((origin_len - *amount)..)
-            .collect::<Vec<_>>();
-        stacks[*destination].extend(to_move);
+impl Solution for Day05 {
+    type Parsed<'a> = (Vec<Vec<&'a str>>, Vec<Move>);
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        finish(input, parse_input(input))
     }
 
-    stacks
-        .iter()
-        .map(|stack| match stack.iter().last() {
-            Some(crated) => crated,
-            None => "",
-        })
-        .collect::<String>()
+    fn part_1(&self, (stacks, moves): &Self::Parsed<'_>) -> String {
+        let mut stacks = stacks.clone();
+        for Move {
+            amount,
+            origin,
+            destination,
+        } in moves.iter()
+        {
+            let origin_len = stacks[*origin].len();
+            let to_move = stacks[*origin]
+                .drain((origin_len - *amount)..)
+                .rev()
+                .collect::<Vec<_>>();
+            stacks[*destination].extend(to_move);
+        }
+
+        top_crates(&stacks)
+    }
+
+    fn part_2(&self, (stacks, moves): &Self::Parsed<'_>) -> String {
+        let mut stacks = stacks.clone();
+        for Move {
+            amount,
+            origin,
+            destination,
+        } in moves.iter()
+        {
+            let origin_len = stacks[*origin].len();
+            let to_move = stacks[*origin]
+                .drain((origin_len - *amount)..)
+                .collect::<Vec<_>>();
+            stacks[*destination].extend(to_move);
+        }
+
+        top_crates(&stacks)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = concat!(
-        "    [D]    \n",
-        "[N] [C]    \n",
-        "[Z] [M] [P]\n",
-        " 1   2   3 \n",
-        "           \n",
-        "move 1 from 2 to 1\n",
-        "move 3 from 1 to 3\n",
-        "move 2 from 2 to 1\n",
-        "move 1 from 1 to 2\n",
-    );
-
     #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "CMZ");
+    fn parses_a_move_with_zero_based_stacks() {
+        let step: Move = "move 3 from 1 to 9".parse().unwrap();
+
+        assert_eq!(
+            (step.amount(), step.origin(), step.destination()),
+            (3, 0, 8)
+        );
     }
 
     #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "MCD");
+    fn rejects_a_move_without_a_destination() {
+        let error = "move 3 from 1 too 9".parse::<Move>().unwrap_err();
+
+        assert_eq!((error.line(), error.column()), (1, 14));
+        assert_eq!(error.expected(), "\" to \"");
     }
 }
+
+common::example_tests!(Day05, example: [part_1, part_2]);
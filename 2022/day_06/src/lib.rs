@@ -1,23 +1,40 @@
 use std::collections::HashSet;
 
-fn find_marker(input: &str, window_size: usize) -> String {
-    let chars = input.chars().collect::<Vec<char>>();
+use common::parse::all_chars;
+use common::{ParseError, Solution};
 
+#[derive(Default)]
+pub struct Day06;
+
+fn find_marker(chars: &[char], window_size: usize) -> Option<usize> {
     let (index, _) = chars
         .windows(window_size)
         .enumerate()
-        .find(|(_, letter)| letter.len() == letter.iter().collect::<HashSet<&char>>().len())
-        .unwrap();
+        .find(|(_, letter)| letter.len() == letter.iter().collect::<HashSet<&char>>().len())?;
 
-    (index + window_size).to_string()
+    Some(index + window_size)
 }
 
-pub fn part_1(input: &str) -> String {
-    find_marker(input, 4)
-}
+impl Solution for Day06 {
+    type Parsed<'a> = Vec<char>;
+    type Answer1 = Result<usize, String>;
+    type Answer2 = Result<usize, String>;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        let signal = input.trim_end();
+        all_chars(input, signal, "a lowercase letter", |ch| {
+            ch.is_ascii_lowercase()
+        })?;
+        Ok(signal.chars().collect())
+    }
+
+    fn part_1(&self, chars: &Vec<char>) -> Result<usize, String> {
+        find_marker(chars, 4).ok_or_else(|| String::from("no 4 different letters in a row"))
+    }
 
-pub fn part_2(input: &str) -> String {
-    find_marker(input, 14)
+    fn part_2(&self, chars: &Vec<char>) -> Result<usize, String> {
+        find_marker(chars, 14).ok_or_else(|| String::from("no 14 different letters in a row"))
+    }
 }
 
 #[cfg(test)]
@@ -25,19 +42,22 @@ mod tests {
     use super::*;
 
     #[test]
-    fn part_1_works() {
-        assert_eq!(part_1("bvwbjplbgvbhsrlpgdmjqwftvncz"), "5");
-        assert_eq!(part_1("nppdvjthqldpwncqszvftbrmjlhg"), "6");
-        assert_eq!(part_1("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), "10");
-        assert_eq!(part_1("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), "11");
-    }
-
-    #[test]
-    fn part_2_works() {
-        assert_eq!(part_2("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), "19");
-        assert_eq!(part_2("bvwbjplbgvbhsrlpgdmjqwftvncz"), "23");
-        assert_eq!(part_2("nppdvjthqldpwncqszvftbrmjlhg"), "23");
-        assert_eq!(part_2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), "29");
-        assert_eq!(part_2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), "26");
+    fn fails_only_the_part_whose_marker_is_missing() {
+        let chars = Day06.parse("abcdabcd\n").unwrap();
+
+        assert_eq!(Day06.part_1(&chars), Ok(4));
+        assert_eq!(
+            Day06.part_2(&chars),
+            Err(String::from("no 14 different letters in a row"))
+        );
     }
 }
+
+common::example_tests!(
+    Day06,
+    example: [part_1, part_2],
+    example_2: [part_1, part_2],
+    example_3: [part_1, part_2],
+    example_4: [part_1, part_2],
+    example_5: [part_1, part_2],
+);
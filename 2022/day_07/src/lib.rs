@@ -1,54 +1,176 @@
-#![feature(iter_intersperse)]
 use std::collections::BTreeMap;
 
+use common::nom::{finish, separated_to_end, tag, IResult};
+use common::{ParseError, Solution};
 use nom::{
     branch::alt,
-    bytes::complete::{is_a, tag},
+    bytes::complete::is_a,
     character::complete::{self, alpha1, newline},
     multi::separated_list1,
     sequence::separated_pair,
-    IResult,
 };
 
-#[derive(Debug)]
-enum Operation<'a> {
+#[derive(Default)]
+pub struct Day07;
+
+/// A command from the terminal output, together with what it printed. With the `serde`
+/// feature, [`OwnedOperation`] is the version that can be deserialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Operation<'a> {
     Cd(Cd<'a>),
     Ls(Vec<Files<'a>>),
 }
 
-#[derive(Debug)]
-enum Cd<'a> {
+/// Where `$ cd` goes: `/`, `..` or a directory by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Cd<'a> {
     Root,
     Up,
     Down(&'a str),
 }
 
-#[derive(Debug)]
-enum Files<'a> {
-    File { size: u32 },
+/// One line listed by `$ ls`. Only file sizes count towards directory sizes, since listed
+/// directories are measured once they are entered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Files<'a> {
+    File { size: u64 },
     Dir(&'a str),
 }
 
-fn nom_file(input: &str) -> IResult<&str, Files> {
+impl Files<'_> {
+    /// The size of a file, or `None` for a directory.
+    pub fn size(&self) -> Option<u64> {
+        match self {
+            Files::File { size } => Some(*size),
+            Files::Dir(_) => None,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Operation<'a> {
+    type Error = ParseError;
+
+    /// Parses one command, e.g. `$ cd a`, or `$ ls` followed by its listing.
+    fn try_from(text: &'a str) -> Result<Self, ParseError> {
+        finish(text, alt((nom_ls, nom_cd))(text))
+    }
+}
+
+/// [`Operation`] with the names it borrows from the input copied.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum OwnedOperation {
+    Cd(OwnedCd),
+    Ls(Vec<OwnedFiles>),
+}
+
+/// [`Cd`] with the directory name copied.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum OwnedCd {
+    Root,
+    Up,
+    Down(String),
+}
+
+/// [`Files`] with the directory name copied.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum OwnedFiles {
+    File { size: u64 },
+    Dir(String),
+}
+
+#[cfg(feature = "serde")]
+impl From<&Operation<'_>> for OwnedOperation {
+    fn from(operation: &Operation<'_>) -> Self {
+        match operation {
+            Operation::Cd(Cd::Root) => OwnedOperation::Cd(OwnedCd::Root),
+            Operation::Cd(Cd::Up) => OwnedOperation::Cd(OwnedCd::Up),
+            Operation::Cd(Cd::Down(name)) => OwnedOperation::Cd(OwnedCd::Down(name.to_string())),
+            Operation::Ls(files) => OwnedOperation::Ls(
+                files
+                    .iter()
+                    .map(|file| match file {
+                        Files::File { size } => OwnedFiles::File { size: *size },
+                        Files::Dir(name) => OwnedFiles::Dir(name.to_string()),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> From<&'a OwnedOperation> for Operation<'a> {
+    fn from(operation: &'a OwnedOperation) -> Self {
+        match operation {
+            OwnedOperation::Cd(OwnedCd::Root) => Operation::Cd(Cd::Root),
+            OwnedOperation::Cd(OwnedCd::Up) => Operation::Cd(Cd::Up),
+            OwnedOperation::Cd(OwnedCd::Down(name)) => Operation::Cd(Cd::Down(name)),
+            OwnedOperation::Ls(files) => Operation::Ls(
+                files
+                    .iter()
+                    .map(|file| match file {
+                        OwnedFiles::File { size } => Files::File { size: *size },
+                        OwnedFiles::Dir(name) => Files::Dir(name),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// The total size of every directory, keyed by the names on its path from the root, which
+/// itself is `[""]`. With the `serde` feature it serializes as a map from paths like `/a/e` to
+/// sizes, since formats such as JSON only allow string keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectorySizes<'a>(BTreeMap<Vec<&'a str>, u64>);
+
+impl<'a> DirectorySizes<'a> {
+    /// The size of the root directory, which holds every file.
+    pub fn total(&self) -> u64 {
+        self.0[&vec![""]]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&[&'a str], u64)> + '_ {
+        self.0.iter().map(|(path, &size)| (path.as_slice(), size))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for DirectorySizes<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter().map(|(path, size)| match path {
+            [""] => (String::from("/"), size),
+            path => (path.join("/"), size),
+        }))
+    }
+}
+
+fn nom_file(input: &str) -> IResult<'_, Files<'_>> {
     let (input, (size, _)) =
-        separated_pair(complete::u32, tag(" "), is_a("qwertyuiopasdfghjklzxcvbnm."))(input)?;
+        separated_pair(complete::u64, tag(" "), is_a("qwertyuiopasdfghjklzxcvbnm."))(input)?;
     Ok((input, Files::File { size }))
 }
 
-fn nom_directory(input: &str) -> IResult<&str, Files> {
+fn nom_directory(input: &str) -> IResult<'_, Files<'_>> {
     let (input, _) = tag("dir ")(input)?;
     let (input, name) = alpha1(input)?;
     Ok((input, Files::Dir(name)))
 }
 
-fn nom_ls(input: &str) -> IResult<&str, Operation> {
+fn nom_ls(input: &str) -> IResult<'_, Operation<'_>> {
     let (input, _) = tag("$ ls")(input)?;
     let (input, _) = newline(input)?;
     let (input, files) = separated_list1(newline, alt((nom_file, nom_directory)))(input)?;
     Ok((input, Operation::Ls(files)))
 }
 
-fn nom_cd(input: &str) -> IResult<&str, Operation> {
+fn nom_cd(input: &str) -> IResult<'_, Operation<'_>> {
     let (input, _) = tag("$ cd ")(input)?;
     let (input, dir) = alt((tag(".."), alpha1, tag("/")))(input)?;
     let operation = match dir {
@@ -59,39 +181,35 @@ fn nom_cd(input: &str) -> IResult<&str, Operation> {
     Ok((input, operation))
 }
 
-fn nom_commands(input: &str) -> IResult<&str, Vec<Operation>> {
-    let (input, commands) = separated_list1(newline, alt((nom_ls, nom_cd)))(input)?;
+fn nom_commands(input: &str) -> IResult<'_, Vec<Operation<'_>>> {
+    let (input, commands) = separated_to_end("\n", alt((nom_ls, nom_cd)))(input)?;
     Ok((input, commands))
 }
 
-fn get_directory_sizes(commands: Vec<Operation>) -> BTreeMap<Vec<&str>, u32> {
+/// Sizes of every directory by path, starting with `""` for the root. Like a shell, `cd ..` in
+/// the root stays there.
+pub fn get_directory_sizes<'a>(commands: &[Operation<'a>]) -> DirectorySizes<'a> {
     let (_, sizes) = commands.iter().fold(
-        (vec![], BTreeMap::new()),
+        (vec![""], BTreeMap::from([(vec![""], 0)])),
         |(mut context, mut sizes), command| {
             match command {
                 Operation::Cd(Cd::Root) => {
-                    context.push("");
+                    context.truncate(1);
                 }
                 Operation::Cd(Cd::Up) => {
-                    context.pop();
+                    if context.len() > 1 {
+                        context.pop();
+                    }
                 }
                 Operation::Cd(Cd::Down(name)) => {
                     context.push(name);
                 }
                 Operation::Ls(files) => {
-                    let sum = files
-                        .iter()
-                        .filter_map(|file| {
-                            if let Files::File { size } = file {
-                                Some(size)
-                            } else {
-                                None
-                            }
-                        })
-                        .sum::<u32>();
+                    let sum = files.iter().filter_map(Files::size).sum::<u64>();
 
                     for i in 0..context.len() {
-                        sizes.entry(context[0..=i].to_vec())
+                        sizes
+                            .entry(context[0..=i].to_vec())
                             .and_modify(|v| *v += sum)
                             .or_insert(sum);
                     }
@@ -100,72 +218,74 @@ fn get_directory_sizes(commands: Vec<Operation>) -> BTreeMap<Vec<&str>, u32> {
             (context, sizes)
         },
     );
-    sizes
-}
-
-pub fn part_1(input: &str) -> String {
-    let (_, commands) = nom_commands(input).unwrap();
-    let sizes = get_directory_sizes(commands);
-    let summed_sizes = sizes
-        .iter()
-        .filter(|(_, &size)| size < 100000)
-        .map(|(_, size)| size)
-        .sum::<u32>();
-    summed_sizes.to_string()
-}
-
-pub fn part_2(input: &str) -> String {
-    let (_, commands) = nom_commands(input).unwrap();
-    let sizes = get_directory_sizes(commands);
-    let total = sizes.get(&vec![""]).unwrap();
-    let target_size = total - 40000000;
-    let mut sizes = sizes
-        .iter()
-        .map(|(_, &size)| size)
-        .filter(|&size| size > target_size)
-        .collect::<Vec<u32>>();
-    sizes.sort();
-    let deletion_size = sizes.iter().next().unwrap();
-    deletion_size.to_string()
+    DirectorySizes(sizes)
+}
+
+impl Solution for Day07 {
+    type Parsed<'a> = DirectorySizes<'a>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        let commands = finish(input, nom_commands(input))?;
+        Ok(get_directory_sizes(&commands))
+    }
+
+    fn part_1(&self, sizes: &DirectorySizes) -> u64 {
+        sizes
+            .iter()
+            .filter(|&(_, size)| size < 100000)
+            .map(|(_, size)| size)
+            .sum::<u64>()
+    }
+
+    /// Returns 0 when there is already enough free space without deleting anything.
+    fn part_2(&self, sizes: &DirectorySizes) -> u64 {
+        let Some(target_size) = sizes.total().checked_sub(40000000) else {
+            return 0;
+        };
+        let mut sizes = sizes
+            .iter()
+            .map(|(_, size)| size)
+            .filter(|&size| size > target_size)
+            .collect::<Vec<u64>>();
+        sizes.sort();
+        let deletion_size = sizes.first().unwrap();
+        *deletion_size
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = concat!(
-        "$ cd /\n",
-        "$ ls\n",
-        "dir a\n",
-        "14848514 b.txt\n",
-        "8504156 c.dat\n",
-        "dir d\n",
-        "$ cd a\n",
-        "$ ls\n",
-        "dir e\n",
-        "29116 f\n",
-        "2557 g\n",
-        "62596 h.lst\n",
-        "$ cd e\n",
-        "$ ls\n",
-        "584 i\n",
-        "$ cd ..\n",
-        "$ cd ..\n",
-        "$ cd d\n",
-        "$ ls\n",
-        "4060174 j\n",
-        "8033020 d.log\n",
-        "5626152 d.ext\n",
-        "7214296 k",
-    );
-
     #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "95437");
+    fn parses_single_commands() {
+        assert_eq!(
+            Operation::try_from("$ ls\ndir a\n14848514 b.txt"),
+            Ok(Operation::Ls(vec![
+                Files::Dir("a"),
+                Files::File { size: 14848514 }
+            ]))
+        );
+        assert_eq!(Operation::try_from("$ cd .."), Ok(Operation::Cd(Cd::Up)));
+        assert_eq!(Operation::try_from("$ cd a\n$ ls").unwrap_err().line(), 2);
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "24933642");
+    fn serializes_models_and_sizes() {
+        let operation = Operation::try_from("$ ls\ndir a\n584 i").unwrap();
+        let json = serde_json::to_string(&OwnedOperation::from(&operation)).unwrap();
+        assert_eq!(json, r#"{"Ls":[{"Dir":"a"},{"File":{"size":584}}]}"#);
+        let owned: OwnedOperation = serde_json::from_str(&json).unwrap();
+        assert_eq!(Operation::from(&owned), operation);
+
+        let commands = ["$ cd /", "$ ls\n1 b", "$ cd a", "$ ls\n2 c"]
+            .map(|command| Operation::try_from(command).unwrap());
+        let json = serde_json::to_string(&get_directory_sizes(&commands)).unwrap();
+        assert_eq!(json, r#"{"/":3,"/a":2}"#);
     }
 }
+
+common::example_tests!(Day07, example: [part_1, part_2]);
@@ -1,18 +1,36 @@
 use std::collections::HashSet;
 
-fn parse_tree_grid(input: &str) -> Vec<Vec<u32>> {
+use common::parse::all_chars;
+use common::{ParseError, Solution};
+
+#[derive(Default)]
+pub struct Day08;
+
+fn parse_tree_grid(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+    let width = input.lines().next().unwrap_or_default().len();
+    if width == 0 {
+        return Err(ParseError::at(input, input, "a row of trees"));
+    }
     input
         .lines()
         .map(|line| {
-            line.chars()
+            all_chars(input, line, "a digit", |ch| ch.is_ascii_digit())?;
+            if line.len() != width {
+                let end = &line[line.len().min(width)..];
+                let expected = format!("a row of {width} trees like the first");
+                return Err(ParseError::at(input, end, expected));
+            }
+            Ok(line
+                .chars()
                 .flat_map(|ch| ch.to_digit(10))
                 .map(|size| size + 1)
-                .collect::<Vec<u32>>()
+                .collect::<Vec<u32>>())
         })
-        .collect::<Vec<Vec<u32>>>()
+        .collect()
 }
 
-fn record_tree_visibility(grid: Vec<Vec<u32>>) -> HashSet<(usize, usize)> {
+#[allow(clippy::needless_range_loop)]
+fn record_tree_visibility(grid: &[Vec<u32>]) -> HashSet<(usize, usize)> {
     let height = grid.len();
     let width = grid[0].len();
     let mut visibility = HashSet::new();
@@ -54,32 +72,32 @@ fn record_tree_visibility(grid: Vec<Vec<u32>>) -> HashSet<(usize, usize)> {
     visibility
 }
 
-fn find_longest_sightline(grid: Vec<Vec<u32>>) -> u32 {
+fn find_longest_sightline(grid: &[Vec<u32>]) -> u32 {
     let mut longest_sightline_score = 0;
     for (i, row) in grid.iter().enumerate() {
         for (j, tree_height) in row.iter().enumerate() {
             let mut views = (0, 0, 0, 0);
             for ii in (0..i).rev() {
-                    views.0 += 1;
+                views.0 += 1;
                 if grid[ii][j] >= *tree_height {
                     break;
                 }
             }
-            for row in grid.iter().skip(i+1) {
-                    views.1 += 1;
+            for row in grid.iter().skip(i + 1) {
+                views.1 += 1;
                 if row[j] >= *tree_height {
                     break;
                 }
             }
             for jj in (0..j).rev() {
-                    views.2 += 1;
+                views.2 += 1;
                 if grid[i][jj] >= *tree_height {
                     break;
                 }
             }
-            for jj in (j + 1)..grid[i].len() {
-                    views.3 += 1;
-                if grid[i][jj] >= *tree_height {
+            for tree in row.iter().skip(j + 1) {
+                views.3 += 1;
+                if tree >= tree_height {
                     break;
                 }
             }
@@ -91,38 +109,23 @@ fn find_longest_sightline(grid: Vec<Vec<u32>>) -> u32 {
     longest_sightline_score
 }
 
-pub fn part_1(input: &str) -> String {
-    let grid = parse_tree_grid(input);
-    let visibility = record_tree_visibility(grid);
-    visibility.len().to_string()
-}
+impl Solution for Day08 {
+    type Parsed<'a> = Vec<Vec<u32>>;
+    type Answer1 = usize;
+    type Answer2 = u32;
 
-pub fn part_2(input: &str) -> String {
-    let grid = parse_tree_grid(input);
-    let best_score = find_longest_sightline(grid);
-    best_score.to_string()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[rustfmt::skip]
-    const INPUT: &str = concat!(
-        "30373\n",
-        "25512\n",
-        "65332\n",
-        "33549\n",
-        "35390\n",
-    );
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_tree_grid(input)
+    }
 
-    #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "21");
+    fn part_1(&self, grid: &Vec<Vec<u32>>) -> usize {
+        let visibility = record_tree_visibility(grid);
+        visibility.len()
     }
 
-    #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "8");
+    fn part_2(&self, grid: &Vec<Vec<u32>>) -> u32 {
+        find_longest_sightline(grid)
     }
 }
+
+common::example_tests!(Day08, example: [part_1, part_2]);
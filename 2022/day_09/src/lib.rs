@@ -1,35 +1,68 @@
 use std::collections::HashSet;
-use std::iter;
+use std::str::FromStr;
 
-#[derive(Clone, Debug)]
-enum Direction {
+use common::parse::{number, split_once};
+#[cfg(feature = "visualize")]
+use common::visual::{Color, Frame, Recorder, Visualize};
+use common::{ParamError, ParseError, Part, Solution};
+
+/// `knots` overrides how many knots follow the head, which the puzzle sets to 1 in part 1
+/// and 9 in part 2.
+#[derive(Default)]
+pub struct Day09 {
+    pub knots: Option<usize>,
+}
+
+/// Which way a motion moves the head of the rope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
     Up,
     Down,
     Right,
     Left,
-    Invalid,
 }
 
-fn simulate_rope(input: &str, knots: usize) -> usize {
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    /// Parses the letter a motion starts with, `U`, `D`, `R` or `L`.
+    fn from_str(letter: &str) -> Result<Self, ParseError> {
+        parse_direction(letter, letter)
+    }
+}
+
+/// Parses `letter`, a slice of `input`.
+fn parse_direction(input: &str, letter: &str) -> Result<Direction, ParseError> {
+    match letter {
+        "U" => Ok(Direction::Up),
+        "D" => Ok(Direction::Down),
+        "R" => Ok(Direction::Right),
+        "L" => Ok(Direction::Left),
+        _ => Err(ParseError::at(input, letter, "'U', 'D', 'R' or 'L'")),
+    }
+}
+
+fn parse_moves(input: &str) -> Result<Vec<(Direction, usize)>, ParseError> {
     input
+        .trim_end()
         .lines()
-        .filter_map(|line| {
-            line.split_once(' ')
-                .map(|(direction, distance)| {
-                    (
-                        match direction {
-                            "U" => Direction::Up,
-                            "D" => Direction::Down,
-                            "R" => Direction::Right,
-                            "L" => Direction::Left,
-                            _ => Direction::Invalid,
-                        },
-                        distance.parse().unwrap_or(0),
-                    )
-                })
-                .map(|(direction, distance)| iter::repeat(direction).take(distance))
+        .map(|line| {
+            let (direction, distance) = split_once(input, line, " ")?;
+            Ok((parse_direction(input, direction)?, number(input, distance)?))
         })
-        .flatten()
+        .collect()
+}
+
+/// Moves the rope and returns how many positions its tail visited. `on_step` sees the head,
+/// the other knots and the visited positions after every step of one square.
+fn simulate_rope(
+    moves: &[(Direction, usize)],
+    knots: usize,
+    mut on_step: impl FnMut((i32, i32), &[(i32, i32)], &HashSet<(i32, i32)>),
+) -> usize {
+    moves
+        .iter()
+        .flat_map(|(direction, distance)| std::iter::repeat_n(direction, *distance))
         .fold(
             ((0_i32, 0_i32), vec![(0, 0); knots], HashSet::from([(0, 0)])),
             |(mut head, mut tails, mut visits), direction| {
@@ -38,10 +71,9 @@ fn simulate_rope(input: &str, knots: usize) -> usize {
                     Direction::Down => head.1 -= 1,
                     Direction::Right => head.0 += 1,
                     Direction::Left => head.0 -= 1,
-                    Direction::Invalid => (),
                 };
                 let mut prev_knot = &head;
-                for mut tail in tails.iter_mut() {
+                for tail in tails.iter_mut() {
                     let diff = (prev_knot.0 - tail.0, prev_knot.1 - tail.1);
                     if diff.0.abs() > 1 || diff.1.abs() > 1 {
                         tail.0 += diff.0.signum();
@@ -50,6 +82,7 @@ fn simulate_rope(input: &str, knots: usize) -> usize {
                     prev_knot = tail;
                 }
                 visits.insert(*prev_knot);
+                on_step(head, &tails, &visits);
                 (head, tails, visits)
             },
         )
@@ -57,50 +90,109 @@ fn simulate_rope(input: &str, knots: usize) -> usize {
         .len()
 }
 
-pub fn part_1(input: &str) -> String {
-    simulate_rope(input, 1).to_string()
+impl Solution for Day09 {
+    type Parsed<'a> = Vec<(Direction, usize)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_moves(input)
+    }
+
+    fn part_1(&self, moves: &Vec<(Direction, usize)>) -> usize {
+        simulate_rope(moves, self.knots(Part::One), |_, _, _| {})
+    }
+
+    fn part_2(&self, moves: &Vec<(Direction, usize)>) -> usize {
+        simulate_rope(moves, self.knots(Part::Two), |_, _, _| {})
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), ParamError> {
+        match name {
+            "knots" => self.knots = Some(ParamError::parse_value(name, value)?),
+            _ => return Err(ParamError::unknown(name)),
+        }
+        Ok(())
+    }
+}
+
+impl Day09 {
+    fn knots(&self, part: Part) -> usize {
+        match part {
+            Part::One => self.knots.unwrap_or(1),
+            Part::Two => self.knots.unwrap_or(9),
+        }
+    }
 }
 
-pub fn part_2(input: &str) -> String {
-    simulate_rope(input, 9).to_string()
+#[cfg(feature = "visualize")]
+const PALETTE: &[Color] = &[
+    Color::new('.', [24, 24, 32]),
+    Color::new('#', [70, 110, 170]),
+    Color::new('o', [230, 200, 120]),
+    Color::new('H', [230, 80, 60]),
+];
+
+/// Draws the whole area the head covers, with the tail's trail beneath the knots.
+#[cfg(feature = "visualize")]
+impl Visualize for Day09 {
+    fn visualize(&self, moves: &Vec<(Direction, usize)>, part: Part, recorder: &mut Recorder) {
+        let knots = self.knots(part);
+        // Every knot stays within the area its leader covered, so the head's bounds are enough.
+        let (mut min, mut max) = ((0, 0), (0, 0));
+        simulate_rope(moves, knots, |head, _, _| {
+            min = (min.0.min(head.0), min.1.min(head.1));
+            max = (max.0.max(head.0), max.1.max(head.1));
+        });
+        let (width, height) = ((max.0 - min.0 + 1) as usize, (max.1 - min.1 + 1) as usize);
+        // Up is towards the top of the frame.
+        let cell = |(x, y): (i32, i32)| ((x - min.0) as usize, (max.1 - y) as usize);
+        let draw = |head, tails: &[(i32, i32)], visits: &HashSet<(i32, i32)>| {
+            let mut frame = Frame::new(PALETTE, width, height);
+            for &visit in visits {
+                let (x, y) = cell(visit);
+                frame.set(x, y, 1);
+            }
+            for &knot in tails {
+                let (x, y) = cell(knot);
+                frame.set(x, y, 2);
+            }
+            let (x, y) = cell(head);
+            frame.set(x, y, 3);
+            frame
+        };
+
+        // The last step is always drawn, so that the animation ends on the finished trail.
+        let steps: usize = moves.iter().map(|(_, distance)| distance).sum();
+        let mut step = 0;
+        simulate_rope(moves, knots, |head, tails, visits| {
+            step += 1;
+            if step == steps {
+                recorder.frame(|| draw(head, tails, visits));
+            } else {
+                recorder.step(|| draw(head, tails, visits));
+            }
+        });
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[rustfmt::skip]
-    const INPUT_1: &str = concat!(
-        "R 4\n",
-        "U 4\n",
-        "L 3\n",
-        "D 1\n",
-        "R 4\n",
-        "D 1\n",
-        "L 5\n",
-        "R 2\n",
-    );
-
-    #[rustfmt::skip]
-    const INPUT_2: &str = concat!(
-        "R 5\n",
-        "U 8\n",
-        "L 8\n",
-        "D 3\n",
-        "R 17\n",
-        "D 10\n",
-        "L 25\n",
-        "U 20\n",
-    );
-
     #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT_1), "13");
+    fn parses_directions() {
+        assert_eq!("U".parse(), Ok(Direction::Up));
+        assert_eq!("L".parse(), Ok(Direction::Left));
     }
 
     #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT_1), "1");
-        assert_eq!(part_2(INPUT_2), "36");
+    fn rejects_a_lowercase_direction() {
+        let error = "u".parse::<Direction>().unwrap_err();
+
+        assert_eq!((error.line(), error.column()), (1, 1));
+        assert_eq!(error.expected(), "'U', 'D', 'R' or 'L'");
     }
 }
+
+common::example_tests!(Day09, example: [part_1, part_2], example_2: [part_2]);
@@ -1,29 +1,65 @@
-#![feature(array_chunks)]
+use std::str::FromStr;
 
-struct DeviceState(i32, i32, Vec<i32>);
+use common::parse::number;
+use common::{Answer, ParseError, Solution};
 
-#[derive(Debug)]
-enum Command {
+#[derive(Default)]
+pub struct Day10;
+
+struct DeviceState(i32, i64, Vec<i64>);
+
+/// An instruction of the CPU: `addx V` adds `V` to the X register and `noop` does nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Command {
     Add(i32),
     Noop,
 }
 
-fn parse_commands(input: &str) -> Vec<Command> {
+impl Command {
+    /// How many cycles the instruction takes to complete.
+    pub fn cycles(&self) -> usize {
+        match self {
+            Command::Add(_) => 2,
+            Command::Noop => 1,
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, ParseError> {
+        parse_command(line, line)
+    }
+}
+
+/// The last cycle part 1 samples the signal strength during.
+const SAMPLED_CYCLES: usize = 220;
+
+/// Cycles drawn on the CRT.
+const SCREEN_CYCLES: usize = 240;
+
+/// Parses `line`, a slice of `input`.
+fn parse_command(input: &str, line: &str) -> Result<Command, ParseError> {
+    match line.strip_prefix("addx ") {
+        Some(amount) => Ok(Command::Add(number(input, amount)?)),
+        None if line == "noop" => Ok(Command::Noop),
+        None => Err(ParseError::at(input, line, "\"addx \" or \"noop\"")),
+    }
+}
+
+fn parse_commands(input: &str) -> Result<Vec<Command>, ParseError> {
     input
         .lines()
-        .filter_map(|line| match line {
-            "noop" => Some(Command::Noop),
-            add => add
-                .split_once(' ')
-                .map(|(_, amount)| Command::Add(amount.parse().ok().unwrap_or(0))),
-        })
+        .map(|line| parse_command(input, line))
         .collect()
 }
 
 fn simulate_add(mut state: DeviceState, amount: &i32) -> DeviceState {
     state = simulate_noop(state);
     state = simulate_noop(state);
-    state.1 += amount;
+    state.1 += i64::from(*amount);
     state
 }
 
@@ -33,207 +69,81 @@ fn simulate_noop(DeviceState(mut clock, x, mut result): DeviceState) -> DeviceSt
     DeviceState(clock, x, result)
 }
 
-fn simulate_screen(commands: Vec<Command>) -> Vec<i32> {
-    commands
+/// The X register during each cycle, failing unless the instructions last `cycles` cycles.
+fn simulate_screen(commands: &[Command], cycles: usize) -> Result<Vec<i64>, String> {
+    let strengths = commands
         .iter()
         .fold(DeviceState(0, 1, vec![]), |state, command| match command {
             Command::Add(amount) => simulate_add(state, amount),
             Command::Noop => simulate_noop(state),
         })
-        .2
+        .2;
+    if strengths.len() < cycles {
+        return Err(format!(
+            "the instructions last {} cycles, not {cycles}",
+            strengths.len()
+        ));
+    }
+    Ok(strengths)
 }
 
-pub fn part_1(input: &str) -> String {
-    let commands = parse_commands(input);
-    let strengths = simulate_screen(commands);
-    (0..6)
-        .map(|tick| 20 + 40 * tick)
-        .map(|clock| strengths[clock - 1] * (clock as i32))
-        .sum::<i32>()
-        .to_string()
-}
+impl Solution for Day10 {
+    type Parsed<'a> = Vec<Command>;
+    type Answer1 = Result<i64, String>;
+    type Answer2 = Result<Answer, String>;
 
-pub fn part_2(input: &str) -> String {
-    let commands = parse_commands(input);
-    let strengths = simulate_screen(commands);
-    strengths.array_chunks::<40>().map(|row| row.iter().enumerate().map(|(index, strength)| {
-        if (*strength - (index as i32)).abs() < 2 {
-            '#'
-        } else {
-            '.'
-        }
-    }).collect()).collect::<Vec<String>>().join("\n")
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_commands(input)
+    }
+
+    fn part_1(&self, commands: &Vec<Command>) -> Result<i64, String> {
+        let strengths = simulate_screen(commands, SAMPLED_CYCLES)?;
+        Ok((0..6)
+            .map(|tick| 20 + 40 * tick)
+            .map(|clock| strengths[clock - 1] * (clock as i64))
+            .sum::<i64>())
+    }
+
+    fn part_2(&self, commands: &Vec<Command>) -> Result<Answer, String> {
+        let strengths = simulate_screen(commands, SCREEN_CYCLES)?;
+        let rows = strengths
+            .as_chunks::<40>()
+            .0
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .map(|(index, strength)| {
+                        if (*strength - (index as i64)).abs() < 2 {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Answer::Grid(rows))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[rustfmt::skip]
-    const INPUT: &str = "addx 15
-addx -11
-addx 6
-addx -3
-addx 5
-addx -1
-addx -8
-addx 13
-addx 4
-noop
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx -35
-addx 1
-addx 24
-addx -19
-addx 1
-addx 16
-addx -11
-noop
-noop
-addx 21
-addx -15
-noop
-noop
-addx -3
-addx 9
-addx 1
-addx -3
-addx 8
-addx 1
-addx 5
-noop
-noop
-noop
-noop
-noop
-addx -36
-noop
-addx 1
-addx 7
-noop
-noop
-noop
-addx 2
-addx 6
-noop
-noop
-noop
-noop
-noop
-addx 1
-noop
-noop
-addx 7
-addx 1
-noop
-addx -13
-addx 13
-addx 7
-noop
-addx 1
-addx -33
-noop
-noop
-noop
-addx 2
-noop
-noop
-noop
-addx 8
-noop
-addx -1
-addx 2
-addx 1
-noop
-addx 17
-addx -9
-addx 1
-addx 1
-addx -3
-addx 11
-noop
-noop
-addx 1
-noop
-addx 1
-noop
-noop
-addx -13
-addx -19
-addx 1
-addx 3
-addx 26
-addx -30
-addx 12
-addx -1
-addx 3
-addx 1
-noop
-noop
-noop
-addx -9
-addx 18
-addx 1
-addx 2
-noop
-noop
-addx 9
-noop
-noop
-noop
-addx -1
-addx 2
-addx -37
-addx 1
-addx 3
-noop
-addx 15
-addx -21
-addx 22
-addx -6
-addx 1
-noop
-addx 2
-addx 1
-noop
-addx -10
-noop
-noop
-addx 20
-addx 1
-addx 2
-addx 2
-addx -6
-addx -11
-noop
-noop
-noop";
-    
-    #[rustfmt::skip]
-    const PART_2_OUTPUT: &str = concat!(
-        "##..##..##..##..##..##..##..##..##..##..\n",
-        "###...###...###...###...###...###...###.\n",
-        "####....####....####....####....####....\n",
-        "#####.....#####.....#####.....#####.....\n",
-        "######......######......######......####\n",
-        "#######.......#######.......#######.....",
-    );
-
     #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "13140");
+    fn parses_commands() {
+        assert_eq!("addx -11".parse(), Ok(Command::Add(-11)));
+        assert_eq!("noop".parse(), Ok(Command::Noop));
     }
 
     #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), PART_2_OUTPUT);
+    fn rejects_an_unknown_instruction() {
+        let error = "subx 3".parse::<Command>().unwrap_err();
+
+        assert_eq!((error.line(), error.column()), (1, 1));
+        assert_eq!(error.expected(), "\"addx \" or \"noop\"");
     }
 }
+
+common::example_tests!(Day10, example: [part_1, part_2]);
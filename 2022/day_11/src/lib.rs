@@ -1,24 +1,61 @@
 use std::collections::VecDeque;
+use std::str::FromStr;
 
+use common::nom::{finish, separated_to_end, tag, IResult};
+use common::{ParamError, ParseError, Part, Solution};
 use nom::{
     branch::alt,
-    bytes::{complete::tag, streaming::take_until},
-    character::complete::{self, alphanumeric1, char, multispace0, newline},
-    multi::{count, separated_list1},
-    sequence::separated_pair,
-    IResult,
+    character::complete::{self, one_of},
+    multi::separated_list1,
+    sequence::{delimited, preceded, separated_pair},
+    Parser,
 };
+use tracing::{debug, trace};
 
-#[derive(Debug, Clone)]
-enum Operation {
+/// `rounds` overrides how long the monkeys play, which the puzzle sets to 20 rounds in part 1
+/// and 10000 in part 2.
+#[derive(Default)]
+pub struct Day11 {
+    pub rounds: Option<usize>,
+}
+
+/// How a monkey changes the worry level of an item it inspects. `old + old` is read as a
+/// multiplication by 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Operation {
     Add(u64),
     Multiply(u64),
-    Square(),
+    Square,
+}
+
+impl Operation {
+    /// The worry level after inspecting an item at `old`, or `None` if it does not fit.
+    pub fn apply(&self, old: u128) -> Option<u128> {
+        match self {
+            Operation::Add(amount) => old.checked_add((*amount).into()),
+            Operation::Multiply(amount) => old.checked_mul((*amount).into()),
+            Operation::Square => old.checked_mul(old),
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = ParseError;
+
+    /// Parses the formula from the notes, e.g. `new = old * 19`.
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        finish(text, preceded(tag("new = old "), parse_operation)(text))
+    }
 }
 
+/// The notes about one monkey, and how many items it has inspected so far. Worry levels are
+/// `u128` so that part 2, which keeps them below the product of the divisors, can square any of
+/// them without overflowing.
 #[derive(Debug, Clone)]
-struct Monkey {
-    items: VecDeque<u64>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Monkey {
+    items: VecDeque<u128>,
     operation: Operation,
     test_divisor: u64,
     if_true_monkey: u64,
@@ -26,76 +63,149 @@ struct Monkey {
     inspections: u64,
 }
 
-fn parse_operation(input: &str) -> IResult<&str, Operation> {
-    let (input, (operation, rhs)) =
-        separated_pair(alt((char('+'), char('*'))), multispace0, alphanumeric1)(input)?;
-    Ok((
-        input,
-        match rhs {
-            "old" => match operation {
-                '+' => Operation::Multiply(2),
-                '*' => Operation::Square(),
-                _ => panic!(),
-            },
-            _ => match operation {
-                '+' => Operation::Add(rhs.parse().unwrap()),
-                '*' => Operation::Multiply(rhs.parse().unwrap()),
-                _ => panic!(),
-            },
-        },
-    ))
+impl Monkey {
+    /// Worry levels of the items the monkey holds, in the order it will inspect them.
+    pub fn items(&self) -> impl Iterator<Item = u128> + '_ {
+        self.items.iter().copied()
+    }
+
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// Items whose worry level is a multiple of this go to [`Self::if_true_monkey`].
+    pub fn test_divisor(&self) -> u64 {
+        self.test_divisor
+    }
+
+    pub fn if_true_monkey(&self) -> u64 {
+        self.if_true_monkey
+    }
+
+    pub fn if_false_monkey(&self) -> u64 {
+        self.if_false_monkey
+    }
+
+    /// How many items the monkey has inspected, which is 0 until the monkeys play.
+    pub fn inspections(&self) -> u64 {
+        self.inspections
+    }
+}
+
+impl FromStr for Monkey {
+    type Err = ParseError;
+
+    /// Parses the notes about one monkey, from its `Monkey 0:` line to its `If false:` line.
+    /// Whether the monkeys it throws to exist is only checked when parsing all of the notes.
+    fn from_str(notes: &str) -> Result<Self, ParseError> {
+        finish(notes, parse_monkey(notes)).map(|(monkey, _)| monkey)
+    }
+}
+
+fn parse_operation(input: &str) -> IResult<'_, Operation> {
+    let (input, (operation, rhs)) = separated_pair(
+        one_of("+*"),
+        tag(" "),
+        alt((tag("old").map(|_| None), complete::u64.map(Some))),
+    )(input)?;
+    let operation = match (operation, rhs) {
+        ('+', None) => Operation::Multiply(2),
+        (_, None) => Operation::Square,
+        ('+', Some(amount)) => Operation::Add(amount),
+        (_, Some(amount)) => Operation::Multiply(amount),
+    };
+    Ok((input, operation))
 }
 
-fn parse_monkey(input: &str) -> IResult<&str, Monkey> {
-    let (input, _) = take_until("items: ")(input)?;
-    let (input, _) = tag("items: ")(input)?;
+/// Where the notes that [`check_notes`] validates start in the input.
+struct NotePositions<'a> {
+    test_divisor: &'a str,
+    if_true_monkey: &'a str,
+    if_false_monkey: &'a str,
+}
+
+fn parse_monkey(input: &str) -> IResult<'_, (Monkey, NotePositions<'_>)> {
+    let (input, _) = delimited(tag("Monkey "), complete::u64, tag(":\n"))(input)?;
+    let (input, _) = tag("  Starting items: ")(input)?;
     let (input, items) = separated_list1(tag(", "), complete::u64)(input)?;
-    let (input, _) = take_until("old ")(input)?;
-    let (input, _) = tag("old ")(input)?;
+    let (input, _) = preceded(tag("\n"), tag("  Operation: new = old "))(input)?;
     let (input, operation) = parse_operation(input)?;
-    let (input, _) = take_until("divisible by ")(input)?;
-    let (input, _) = tag("divisible by ")(input)?;
+    let (input, _) = preceded(tag("\n"), tag("  Test: divisible by "))(input)?;
+    let test_divisor_position = input;
     let (input, test_divisor) = complete::u64(input)?;
-    let (input, _) = take_until("monkey ")(input)?;
-    let (input, _) = tag("monkey ")(input)?;
+    let (input, _) = preceded(tag("\n"), tag("    If true: throw to monkey "))(input)?;
+    let if_true_position = input;
     let (input, if_true_monkey) = complete::u64(input)?;
-    let (input, _) = take_until("monkey ")(input)?;
-    let (input, _) = tag("monkey ")(input)?;
+    let (input, _) = preceded(tag("\n"), tag("    If false: throw to monkey "))(input)?;
+    let if_false_position = input;
     let (input, if_false_monkey) = complete::u64(input)?;
     Ok((
         input,
-        Monkey {
-            items: VecDeque::from(items),
-            operation,
-            test_divisor,
-            if_true_monkey,
-            if_false_monkey,
-            inspections: 0,
-        },
+        (
+            Monkey {
+                items: items.into_iter().map(u128::from).collect(),
+                operation,
+                test_divisor,
+                if_true_monkey,
+                if_false_monkey,
+                inspections: 0,
+            },
+            NotePositions {
+                test_divisor: test_divisor_position,
+                if_true_monkey: if_true_position,
+                if_false_monkey: if_false_position,
+            },
+        ),
     ))
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Monkey>> {
-    let (input, monkeys) = separated_list1(count(newline, 2), parse_monkey)(input)?;
+fn parse_input(input: &str) -> IResult<'_, Vec<(Monkey, NotePositions<'_>)>> {
+    let (input, monkeys) = separated_to_end("\n\n", parse_monkey)(input)?;
     Ok((input, monkeys))
 }
 
-fn simulate_keep_away<F>(monkeys: &mut Vec<Monkey>, rounds: usize, inspection_callback: F)
+/// Rejects notes that cannot be played: throws to a missing monkey or back to the thrower, and
+/// a divisor of 0 or one that takes the product of all divisors past 64 bits. Whether worry
+/// levels overflow depends on the part and its rounds, so playing checks that instead.
+fn check_notes(input: &str, notes: &[(Monkey, NotePositions)]) -> Result<(), ParseError> {
+    let mut test_product: u64 = 1;
+    for (i, (monkey, positions)) in notes.iter().enumerate() {
+        for (target, position) in [
+            (monkey.if_true_monkey, positions.if_true_monkey),
+            (monkey.if_false_monkey, positions.if_false_monkey),
+        ] {
+            if target as usize >= notes.len() || target as usize == i {
+                return Err(ParseError::at(input, position, "another monkey's number"));
+            }
+        }
+        test_product = match test_product.checked_mul(monkey.test_divisor) {
+            Some(product) if product > 0 => product,
+            _ => {
+                let expected = "a divisor from 1 that keeps the product of all within 64 bits";
+                return Err(ParseError::at(input, positions.test_divisor, expected));
+            }
+        };
+    }
+    Ok(())
+}
+
+/// Fails with the index of the monkey whose operation overflowed a worry level.
+fn simulate_keep_away<F>(
+    monkeys: &mut [Monkey],
+    rounds: usize,
+    inspection_callback: F,
+) -> Result<(), usize>
 where
-    F: Fn(u64) -> u64,
+    F: Fn(u128) -> u128,
 {
-    for _ in 0..rounds {
+    for round in 1..=rounds {
         for i in 0..monkeys.len() {
             let current_monkey = monkeys[i].clone();
             for j in 0..current_monkey.items.len() {
-                let mut item = current_monkey.items[j];
-                match current_monkey.operation {
-                    Operation::Add(ref amount) => item += amount,
-                    Operation::Multiply(ref amount) => item *= amount,
-                    Operation::Square() => item *= item,
-                }
-                item = inspection_callback(item);
-                if item % current_monkey.test_divisor == 0 {
+                let item = current_monkey.items[j];
+                let item = current_monkey.operation.apply(item).ok_or(i)?;
+                let item = inspection_callback(item);
+                if item.is_multiple_of(current_monkey.test_divisor.into()) {
                     monkeys
                         .get_mut(current_monkey.if_true_monkey as usize)
                         .unwrap()
@@ -112,7 +222,14 @@ where
             }
             monkeys.get_mut(i).unwrap().items.clear();
         }
+        trace!(round, inspections = ?inspections(monkeys), "round over");
     }
+    debug!(rounds, inspections = ?inspections(monkeys), "keep away over");
+    Ok(())
+}
+
+fn inspections(monkeys: &[Monkey]) -> Vec<u64> {
+    monkeys.iter().map(|monkey| monkey.inspections).collect()
 }
 
 fn sum_two_most_active(monkeys: &[Monkey]) -> u64 {
@@ -130,63 +247,255 @@ fn sum_two_most_active(monkeys: &[Monkey]) -> u64 {
     first * second
 }
 
-pub fn part_1(input: &str) -> String {
-    let (_, mut monkeys) = parse_input(input).unwrap();
-    simulate_keep_away(&mut monkeys, 20, |item| item / 3);
-    sum_two_most_active(&monkeys).to_string()
+impl Day11 {
+    /// Plays keep away for the rounds of `part`, returning the monkeys as they end up, with
+    /// their inspection counts. Worry levels follow the rules of the part: divided by 3 after
+    /// each inspection in part 1, and kept below the product of the divisors in part 2.
+    ///
+    /// Fails when an operation takes a worry level past 128 bits, which only large numbers in
+    /// part 1 can do.
+    pub fn play(&self, monkeys: &[Monkey], part: Part) -> Result<Vec<Monkey>, String> {
+        let mut monkeys = monkeys.to_vec();
+        match part {
+            Part::One => {
+                simulate_keep_away(&mut monkeys, self.rounds.unwrap_or(20), |item| item / 3)
+            }
+            Part::Two => {
+                let test_product = monkeys
+                    .iter()
+                    .map(|Monkey { test_divisor, .. }| u128::from(*test_divisor))
+                    .product::<u128>();
+                simulate_keep_away(&mut monkeys, self.rounds.unwrap_or(10000), |item| {
+                    item % test_product
+                })
+            }
+        }
+        .map_err(|i| format!("monkey {i}'s operation takes a worry level past 128 bits"))?;
+        Ok(monkeys)
+    }
 }
 
-pub fn part_2(input: &str) -> String {
-    let (_, mut monkeys) = parse_input(input).unwrap();
-    let test_product = monkeys
-        .iter()
-        .map(|Monkey { test_divisor, .. }| test_divisor)
-        .product::<u64>();
-    simulate_keep_away(&mut monkeys, 10000, |item| item % test_product);
-    sum_two_most_active(&monkeys).to_string()
+impl Solution for Day11 {
+    type Parsed<'a> = Vec<Monkey>;
+    type Answer1 = Result<u64, String>;
+    type Answer2 = Result<u64, String>;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        let notes = finish(input, parse_input(input))?;
+        check_notes(input, &notes)?;
+        Ok(notes.into_iter().map(|(monkey, _)| monkey).collect())
+    }
+
+    fn part_1(&self, monkeys: &Vec<Monkey>) -> Result<u64, String> {
+        Ok(sum_two_most_active(&self.play(monkeys, Part::One)?))
+    }
+
+    fn part_2(&self, monkeys: &Vec<Monkey>) -> Result<u64, String> {
+        Ok(sum_two_most_active(&self.play(monkeys, Part::Two)?))
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), ParamError> {
+        match name {
+            "rounds" => self.rounds = Some(ParamError::parse_value(name, value)?),
+            _ => return Err(ParamError::unknown(name)),
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[rustfmt::skip]
-    const INPUT: &str = "Monkey 0:
-  Starting items: 79, 98
-  Operation: new = old * 19
-  Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
-
-Monkey 1:
-  Starting items: 54, 65, 75, 74
-  Operation: new = old + 6
-  Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
-
-Monkey 2:
-  Starting items: 79, 60, 97
-  Operation: new = old * old
-  Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
-
-Monkey 3:
-  Starting items: 74
-  Operation: new = old + 3
-  Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1
-";
+    #[test]
+    fn parses_one_monkey() {
+        let monkey: Monkey = [
+            "Monkey 0:",
+            "  Starting items: 79, 98",
+            "  Operation: new = old * 19",
+            "  Test: divisible by 23",
+            "    If true: throw to monkey 2",
+            "    If false: throw to monkey 3",
+        ]
+        .join("\n")
+        .parse()
+        .unwrap();
+
+        assert_eq!(monkey.items().collect::<Vec<_>>(), [79, 98]);
+        assert_eq!(monkey.operation(), Operation::Multiply(19));
+        assert_eq!(monkey.operation().apply(79), Some(1501));
+        assert_eq!(monkey.test_divisor(), 23);
+        assert_eq!((monkey.if_true_monkey(), monkey.if_false_monkey()), (2, 3));
+        assert_eq!("new = old + old".parse(), Ok(Operation::Multiply(2)));
+    }
 
     #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "10605");
+    fn overflowing_worry_levels_fail_only_the_part_they_happen_in() {
+        let notes = [
+            "Monkey 0:",
+            "  Starting items: 1000",
+            "  Operation: new = old * old",
+            "  Test: divisible by 2",
+            "    If true: throw to monkey 1",
+            "    If false: throw to monkey 1",
+            "",
+            "Monkey 1:",
+            "  Starting items: 1",
+            "  Operation: new = old + 1",
+            "  Test: divisible by 3",
+            "    If true: throw to monkey 0",
+            "    If false: throw to monkey 0",
+        ]
+        .join("\n");
+        let day = Day11 { rounds: Some(10) };
+        let monkeys = day.parse(&notes).unwrap();
+
+        assert_eq!(
+            day.part_1(&monkeys),
+            Err(String::from(
+                "monkey 0's operation takes a worry level past 128 bits"
+            ))
+        );
+        assert_eq!(day.part_2(&monkeys), Ok(380));
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "2713310158");
+    fn serializes_inspection_counts() {
+        let monkeys = Day11::default()
+            .parse(include_str!("../assets/example.txt"))
+            .unwrap();
+        let played = Day11 { rounds: Some(1) }.play(&monkeys, Part::One).unwrap();
+        let json = serde_json::to_value(&played).unwrap();
+
+        assert_eq!(json[0]["inspections"], 2);
+        assert_eq!(json[0]["items"], serde_json::json!([20, 23, 27, 26]));
+        assert_eq!(json[2]["operation"], serde_json::json!("Square"));
+        let monkey: Monkey = serde_json::from_value(json[0].clone()).unwrap();
+        assert_eq!(monkey.inspections(), 2);
+    }
+}
+
+#[cfg(test)]
+mod properties {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// A monkey as written in the puzzle, with its operation kept as the text after `old `.
+    #[derive(Debug, Clone)]
+    struct MonkeyNotes {
+        items: Vec<u64>,
+        operation: (char, Option<u64>),
+        test_divisor: u64,
+        if_true_monkey: usize,
+        if_false_monkey: usize,
+    }
+
+    fn monkeys() -> impl Strategy<Value = Vec<MonkeyNotes>> {
+        (2..6_usize).prop_flat_map(|count| {
+            let monkey = (
+                prop::collection::vec(1..100_u64, 1..5),
+                (
+                    prop::sample::select(vec!['+', '*']),
+                    prop::option::of(1..20_u64),
+                ),
+                prop::sample::select(vec![2, 3, 5, 7, 11, 13, 17, 19, 23]),
+                0..count,
+                0..count,
+            )
+                .prop_map(
+                    |(items, operation, test_divisor, if_true_monkey, if_false_monkey)| {
+                        MonkeyNotes {
+                            items,
+                            operation,
+                            test_divisor,
+                            if_true_monkey,
+                            if_false_monkey,
+                        }
+                    },
+                );
+            prop::collection::vec(monkey, count).prop_filter(
+                "monkeys never throw to themselves",
+                |monkeys| {
+                    monkeys.iter().enumerate().all(|(i, monkey)| {
+                        monkey.if_true_monkey != i && monkey.if_false_monkey != i
+                    })
+                },
+            )
+        })
+    }
+
+    fn render(monkeys: &[MonkeyNotes]) -> String {
+        let monkeys: Vec<String> = monkeys
+            .iter()
+            .enumerate()
+            .map(|(i, monkey)| {
+                let items: Vec<String> = monkey.items.iter().map(u64::to_string).collect();
+                let (operator, operand) = monkey.operation;
+                let operand = operand.map_or_else(|| String::from("old"), |n| n.to_string());
+                [
+                    format!("Monkey {i}:"),
+                    format!("  Starting items: {}", items.join(", ")),
+                    format!("  Operation: new = old {operator} {operand}"),
+                    format!("  Test: divisible by {}", monkey.test_divisor),
+                    format!("    If true: throw to monkey {}", monkey.if_true_monkey),
+                    format!("    If false: throw to monkey {}", monkey.if_false_monkey),
+                ]
+                .join("\n")
+            })
+            .collect();
+        monkeys.join("\n\n") + "\n"
+    }
+
+    /// Plays `rounds` rounds with exact worry levels, evaluating each operation as written.
+    /// Returns `None` when a worry level no longer fits in a `u64`.
+    fn play(monkeys: &[MonkeyNotes], rounds: usize, relief: u64) -> Option<u64> {
+        let mut items: Vec<Vec<u64>> = monkeys.iter().map(|monkey| monkey.items.clone()).collect();
+        let mut inspections = vec![0; monkeys.len()];
+        for _ in 0..rounds {
+            for (i, monkey) in monkeys.iter().enumerate() {
+                for old in std::mem::take(&mut items[i]) {
+                    let operand = monkey.operation.1.unwrap_or(old);
+                    let new = match monkey.operation.0 {
+                        '+' => old.checked_add(operand)?,
+                        _ => old.checked_mul(operand)?,
+                    } / relief;
+                    let target = if new.is_multiple_of(monkey.test_divisor) {
+                        monkey.if_true_monkey
+                    } else {
+                        monkey.if_false_monkey
+                    };
+                    items[target].push(new);
+                    inspections[i] += 1;
+                }
+            }
+        }
+        inspections.sort_unstable();
+        Some(inspections.iter().rev().take(2).product())
+    }
+
+    proptest! {
+        #[test]
+        fn part_1_matches_exact_play(monkeys in monkeys(), rounds in 1..=20_usize) {
+            let expected = play(&monkeys, rounds, 3);
+            prop_assume!(expected.is_some());
+
+            let day = Day11 { rounds: Some(rounds) };
+            let parsed = day.parse(&render(&monkeys)).unwrap();
+            prop_assert_eq!(day.part_1(&parsed).ok(), expected);
+        }
+
+        #[test]
+        fn part_2_matches_exact_play(monkeys in monkeys(), rounds in 1..=4_usize) {
+            let expected = play(&monkeys, rounds, 1);
+            prop_assume!(expected.is_some());
+
+            let day = Day11 { rounds: Some(rounds) };
+            let parsed = day.parse(&render(&monkeys)).unwrap();
+            prop_assert_eq!(day.part_2(&parsed).ok(), expected);
+        }
     }
 }
+
+common::example_tests!(Day11, example: [part_1, part_2]);
@@ -1,61 +1,119 @@
-use nom::{
-    character::{
-        complete::{newline, satisfy},
-        is_alphabetic,
-    },
-    multi::{many1, separated_list1},
-    IResult,
-};
+use std::str::FromStr;
+
+use common::nom::{finish, separated_to_end, IResult};
+#[cfg(feature = "visualize")]
+use common::visual::{Color, Frame, Recorder, Visualize};
+#[cfg(feature = "visualize")]
+use common::Part;
+use common::{ParseError, Solution};
+use nom::{character::complete::satisfy, error::context, multi::many1};
+
+#[derive(Default)]
+pub struct Day12;
 
-#[derive(Debug)]
-enum TileType {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TileType {
     Start,
     End,
     Path,
 }
 
-#[derive(Debug)]
-struct Tile {
+/// One square of the heightmap, with its distance to the end once the grid has been walked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tile {
     tile_type: TileType,
     distance: u32,
     elevation: u32,
 }
 
-fn parse_row(input: &str) -> IResult<&str, Vec<Tile>> {
-    let (input, row) = many1(satisfy(|ch| is_alphabetic(ch as u8)))(input)?;
-    let row = row
-        .iter()
-        .map(|&tile| Tile {
-            tile_type: match tile {
+impl Tile {
+    /// A square not yet walked, from its letter in the heightmap. The start is at elevation
+    /// `a` and the end at `z`.
+    fn new(square: char) -> Self {
+        Tile {
+            tile_type: match square {
                 'S' => TileType::Start,
                 'E' => TileType::End,
                 _ => TileType::Path,
             },
-            elevation: match tile {
+            elevation: match square {
                 'S' => 'a' as u32,
                 'E' => 'z' as u32,
                 elevation => elevation as u32,
             },
             distance: u32::MAX,
-        })
-        .collect();
-    Ok((input, row))
+        }
+    }
+}
+
+impl FromStr for Tile {
+    type Err = ParseError;
+
+    /// Parses one square of the heightmap, a lowercase letter, `S` or `E`.
+    fn from_str(square: &str) -> Result<Self, ParseError> {
+        finish(square, parse_square(square)).map(Tile::new)
+    }
+}
+
+const SQUARE: &str = "a lowercase letter, 'S' or 'E'";
+
+fn parse_square(input: &str) -> IResult<'_, char> {
+    context(
+        SQUARE,
+        satisfy(|ch| ch.is_ascii_lowercase() || ch == 'S' || ch == 'E'),
+    )(input)
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Vec<Tile>>> {
-    let (input, grid) = separated_list1(newline, parse_row)(input)?;
+fn parse_row(input: &str) -> IResult<'_, Vec<Tile>> {
+    let (input, row) = context(SQUARE, many1(parse_square))(input)?;
+    Ok((input, row.into_iter().map(Tile::new).collect()))
+}
+
+fn parse_input(input: &str) -> IResult<'_, Vec<Vec<Tile>>> {
+    let (input, grid) = separated_to_end("\n", parse_row)(input)?;
     Ok((input, grid))
 }
 
-fn walk_grid(grid: &mut Vec<Vec<Tile>>, end: (usize, usize)) {
+/// Checks that the grid is rectangular with exactly one start and one end.
+fn check_grid(input: &str, grid: &[Vec<Tile>]) -> Result<(), ParseError> {
+    let width = grid[0].len();
+    for (line, row) in input.lines().zip(grid) {
+        if row.len() != width {
+            let end = &line[row.len().min(width)..];
+            let expected = format!("a row of {width} squares like the first");
+            return Err(ParseError::at(input, end, expected));
+        }
+    }
+    for marker in ['S', 'E'] {
+        let mut positions = input.match_indices(marker);
+        if positions.next().is_none() {
+            let expected = format!("a square marked {marker:?}");
+            return Err(ParseError::at(input, &input[input.len()..], expected));
+        }
+        if let Some((offset, _)) = positions.next() {
+            let expected = format!("only one square marked {marker:?}");
+            return Err(ParseError::at_offset(input, offset, expected));
+        }
+    }
+    Ok(())
+}
+
+/// Finds every square's distance to `end`, calling `on_visit` with the grid each time a square
+/// is taken off the stack to update its neighbours.
+fn walk_grid(
+    grid: &mut [Vec<Tile>],
+    end: (usize, usize),
+    mut on_visit: impl FnMut(&[Vec<Tile>], (usize, usize)),
+) {
     let mut stack = vec![end];
     grid[end.0][end.1].distance = 0;
     while let Some((i, j)) = stack.pop() {
+        on_visit(grid, (i, j));
         let current_distance = grid[i][j].distance;
         let current_elevation = grid[i][j].elevation;
 
         if i > 0 {
-            let mut up = &mut grid[i - 1][j];
+            let up = &mut grid[i - 1][j];
             if up.elevation >= current_elevation - 1 && up.distance > current_distance + 1 {
                 up.distance = current_distance + 1;
                 stack.push((i - 1, j));
@@ -63,7 +121,7 @@ fn walk_grid(grid: &mut Vec<Vec<Tile>>, end: (usize, usize)) {
         }
 
         if i < grid.len() - 1 {
-            let mut down = &mut grid[i + 1][j];
+            let down = &mut grid[i + 1][j];
             if down.elevation >= current_elevation - 1 && down.distance > current_distance + 1 {
                 down.distance = current_distance + 1;
                 stack.push((i + 1, j));
@@ -71,7 +129,7 @@ fn walk_grid(grid: &mut Vec<Vec<Tile>>, end: (usize, usize)) {
         }
 
         if j > 0 {
-            let mut left = &mut grid[i][j - 1];
+            let left = &mut grid[i][j - 1];
             if left.elevation >= current_elevation - 1 && left.distance > current_distance + 1 {
                 left.distance = current_distance + 1;
                 stack.push((i, j - 1));
@@ -79,7 +137,7 @@ fn walk_grid(grid: &mut Vec<Vec<Tile>>, end: (usize, usize)) {
         }
 
         if j < grid[0].len() - 1 {
-            let mut right = &mut grid[i][j + 1];
+            let right = &mut grid[i][j + 1];
             if right.elevation >= current_elevation - 1 && right.distance > current_distance + 1 {
                 right.distance = current_distance + 1;
                 stack.push((i, j + 1));
@@ -88,73 +146,172 @@ fn walk_grid(grid: &mut Vec<Vec<Tile>>, end: (usize, usize)) {
     }
 }
 
-pub fn part_1(input: &str) -> String {
-    let (_, mut grid) = parse_input(input).unwrap();
+impl Solution for Day12 {
+    type Parsed<'a> = Vec<Vec<Tile>>;
+    type Answer1 = u32;
+    type Answer2 = u32;
 
-    let mut end = None;
-    let mut start = None;
-    for (i, row) in grid.iter().enumerate() {
-        for (j, tile) in row.iter().enumerate() {
-            if let TileType::Start = tile.tile_type {
-                start = Some((i, j));
-            }
-            if let TileType::End = tile.tile_type {
-                end = Some((i, j));
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        let grid = finish(input, parse_input(input))?;
+        check_grid(input, &grid)?;
+        Ok(grid)
+    }
+
+    fn part_1(&self, grid: &Vec<Vec<Tile>>) -> u32 {
+        let mut grid = grid.clone();
+
+        let mut end = None;
+        let mut start = None;
+        for (i, row) in grid.iter().enumerate() {
+            for (j, tile) in row.iter().enumerate() {
+                if let TileType::Start = tile.tile_type {
+                    start = Some((i, j));
+                }
+                if let TileType::End = tile.tile_type {
+                    end = Some((i, j));
+                }
             }
         }
+
+        walk_grid(&mut grid, end.unwrap(), |_, _| {});
+        grid[start.unwrap().0][start.unwrap().1].distance
     }
 
-    walk_grid(&mut grid, end.unwrap());
-    grid[start.unwrap().0][start.unwrap().1]
-        .distance
-        .to_string()
+    fn part_2(&self, grid: &Vec<Vec<Tile>>) -> u32 {
+        let mut grid = grid.clone();
+
+        let mut end = None;
+        let mut candidates = vec![];
+        for (i, row) in grid.iter().enumerate() {
+            for (j, tile) in row.iter().enumerate() {
+                if let TileType::End = tile.tile_type {
+                    end = Some((i, j));
+                }
+                if tile.elevation == 'a' as u32 {
+                    candidates.push((i, j));
+                }
+            }
+        }
+
+        walk_grid(&mut grid, end.unwrap(), |_, _| {});
+        candidates
+            .iter()
+            .map(|&(i, j)| grid[i][j].distance)
+            .min()
+            .unwrap()
+    }
 }
 
-pub fn part_2(input: &str) -> String {
-    let (_, mut grid) = parse_input(input).unwrap();
+/// Palette entries from `REACHED` on shade squares by elevation once their distance is known,
+/// and those below it shade the squares not reached yet.
+#[cfg(feature = "visualize")]
+const REACHED: u8 = 26;
+#[cfg(feature = "visualize")]
+const VISITING: u8 = 2 * REACHED;
+#[cfg(feature = "visualize")]
+const ROUTE: u8 = VISITING + 1;
+
+#[cfg(feature = "visualize")]
+const PALETTE: &[Color] = &{
+    let mut palette = [Color::new('@', [250, 70, 50]); ROUTE as usize + 1];
+    let mut elevation = 0;
+    while elevation < REACHED {
+        let glyph = (b'a' + elevation) as char;
+        let shade = 40 + elevation * 7;
+        palette[elevation as usize] = Color::new(glyph, [shade / 2, shade / 2, shade / 2]);
+        palette[(REACHED + elevation) as usize] = Color::new(glyph, [shade / 4, shade, shade / 2]);
+        elevation += 1;
+    }
+    palette[ROUTE as usize] = Color::new('*', [250, 220, 80]);
+    palette
+};
 
-    let mut end = None;
-    let mut candidates = vec![];
+#[cfg(feature = "visualize")]
+fn draw_grid(grid: &[Vec<Tile>]) -> Frame {
+    let mut frame = Frame::new(PALETTE, grid[0].len(), grid.len());
     for (i, row) in grid.iter().enumerate() {
         for (j, tile) in row.iter().enumerate() {
-            if let TileType::End = tile.tile_type {
-                end = Some((i, j));
-            }
-            if tile.elevation == 'a' as u32 {
-                candidates.push((i, j));
-            }
+            let shade = (tile.elevation - 'a' as u32) as u8;
+            let reached = tile.distance != u32::MAX;
+            frame.set(j, i, if reached { REACHED + shade } else { shade });
         }
     }
+    frame
+}
 
-    walk_grid(&mut grid, end.unwrap());
-    candidates
-        .iter()
-        .map(|&(i, j)| grid[i][j].distance)
-        .min()
-        .unwrap()
-        .to_string()
+/// Draws the search spreading downhill from the end, then the shortest route from the start, or
+/// from the nearest lowest square in part 2.
+#[cfg(feature = "visualize")]
+impl Visualize for Day12 {
+    fn visualize(&self, grid: &Vec<Vec<Tile>>, part: Part, recorder: &mut Recorder) {
+        let mut grid = grid.clone();
+        let (height, width) = (grid.len(), grid[0].len());
+        let squares = || (0..height).flat_map(move |i| (0..width).map(move |j| (i, j)));
+        let end = squares()
+            .find(|&(i, j)| matches!(grid[i][j].tile_type, TileType::End))
+            .unwrap();
+
+        walk_grid(&mut grid, end, |grid, (i, j)| {
+            recorder.step(|| {
+                let mut frame = draw_grid(grid);
+                frame.set(j, i, VISITING);
+                frame
+            });
+        });
+
+        let start = squares().filter(|&(i, j)| match part {
+            Part::One => matches!(grid[i][j].tile_type, TileType::Start),
+            Part::Two => grid[i][j].elevation == 'a' as u32,
+        });
+        let mut frame = draw_grid(&grid);
+        let (mut i, mut j) = start.min_by_key(|&(i, j)| grid[i][j].distance).unwrap();
+        // A start that cannot reach the end has no route to draw.
+        if grid[i][j].distance != u32::MAX {
+            frame.set(j, i, ROUTE);
+        }
+        while grid[i][j].distance != 0 && grid[i][j].distance != u32::MAX {
+            let tile = &grid[i][j];
+            // Each step along the route is one closer to the end and at most one higher.
+            let neighbours = [
+                (i.wrapping_sub(1), j),
+                (i + 1, j),
+                (i, j.wrapping_sub(1)),
+                (i, j + 1),
+            ];
+            (i, j) = neighbours
+                .into_iter()
+                .find(|&(i, j)| {
+                    grid.get(i).and_then(|row| row.get(j)).is_some_and(|next| {
+                        next.distance == tile.distance - 1 && next.elevation <= tile.elevation + 1
+                    })
+                })
+                .unwrap();
+            frame.set(j, i, ROUTE);
+        }
+        recorder.frame(|| frame);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[rustfmt::skip]
-    const INPUT: &str = concat!(
-        "Sabqponm\n",
-        "abcryxxl\n",
-        "accszExk\n",
-        "acctuvwj\n",
-        "abdefghi\n",
-    );
-
     #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "31");
+    fn parses_the_start_at_the_lowest_elevation() {
+        let tile: Tile = "S".parse().unwrap();
+
+        assert_eq!(tile.tile_type, TileType::Start);
+        assert_eq!(tile.elevation, 'a' as u32);
+        assert_eq!("q".parse::<Tile>().unwrap().elevation, 'q' as u32);
     }
 
     #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "29");
+    fn rejects_an_uppercase_elevation() {
+        let error = "Q".parse::<Tile>().unwrap_err();
+
+        assert_eq!((error.line(), error.column()), (1, 1));
+        assert_eq!(error.expected(), "a lowercase letter, 'S' or 'E'");
     }
 }
+
+common::example_tests!(Day12, example: [part_1, part_2]);
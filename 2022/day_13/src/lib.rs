@@ -1,18 +1,58 @@
+use std::fmt;
+use std::str::FromStr;
+
+use common::nom::{finish, separated_to_end, tag, IResult};
+use common::{ParseError, Solution};
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::newline,
-    multi::{separated_list0, separated_list1},
-    sequence::{delimited, pair, separated_pair},
-    IResult, Parser,
+    branch::alt, character::complete::newline, multi::separated_list0, sequence::delimited, Parser,
 };
 
+#[derive(Default)]
+pub struct Day13;
+
+/// A packet of the distress signal: an integer or a list of packets. Packets compare in the
+/// order the puzzle defines, so an integer equals the list holding just that integer. With the
+/// `serde` feature, packets serialize as plain numbers and arrays, which is also how the signal
+/// writes them.
 #[derive(Debug, Eq, Clone)]
-enum Packet {
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
+pub enum Packet {
     List(Vec<Packet>),
     Element(u32),
 }
 
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    /// Parses a packet written like in the signal, e.g. `[1,[2,3]]`.
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        finish(text, parse_packet(text))
+    }
+}
+
+/// Writes the packet back the way the signal lists it.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Packet::Element(value) => write!(f, "{value}"),
+            Packet::List(packets) => {
+                write!(f, "[")?;
+                for (i, packet) in packets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{packet}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
 impl PartialOrd for Packet {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -41,95 +81,183 @@ impl PartialEq for Packet {
     }
 }
 
-fn parse_packet(input: &str) -> IResult<&str, Packet> {
+fn parse_packet(input: &str) -> IResult<'_, Packet> {
     alt((
         nom::character::complete::u32.map(Packet::Element),
         delimited(tag("["), separated_list0(tag(","), parse_packet), tag("]")).map(Packet::List),
     ))(input)
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<(Packet, Packet)>> {
-    separated_list1(
-        pair(newline, newline),
-        separated_pair(parse_packet, newline, parse_packet),
-    )(input)
+fn parse_pair(input: &str) -> IResult<'_, (Packet, Packet)> {
+    let (input, left) = parse_packet(input)?;
+    let (input, _) = newline(input)?;
+    let (input, right) = parse_packet(input)?;
+    Ok((input, (left, right)))
 }
 
-pub fn part_1(input: &str) -> String {
-    let (_, pairs) = parse_input(input).unwrap();
-    pairs
-        .iter()
-        .enumerate()
-        .map(|(i, (packet1, packet2))| match packet1.cmp(packet2) {
-            std::cmp::Ordering::Less => i + 1,
-            std::cmp::Ordering::Equal => panic!("Pairs must have an ordering."),
-            std::cmp::Ordering::Greater => 0,
-        })
-        .sum::<usize>()
-        .to_string()
+fn parse_input(input: &str) -> IResult<'_, Vec<(Packet, Packet)>> {
+    separated_to_end("\n\n", parse_pair)(input)
 }
 
-pub fn part_2(input: &str) -> String {
-    let (_, pairs) = parse_input(input).unwrap();
-    
-    let divider1 = Packet::List(vec![Packet::List(vec![Packet::Element(2)])]);
-    let divider2 = Packet::List(vec![Packet::List(vec![Packet::Element(6)])]);
-
-    let mut packets = pairs
-        .iter()
-        .flat_map(|(packet1, packet2)| [packet1, packet2])
-        .chain([&divider1, &divider2])
-        .collect::<Vec<&Packet>>();
-
-    packets.sort();
-
-    packets.iter().enumerate().filter_map(|(i, &packet)|
-        if packet == &divider1 || packet == &divider2 {
-            Some(i+1)
-        } else {
-            None
-        }
-    ).product::<usize>().to_string()
+impl Solution for Day13 {
+    type Parsed<'a> = Vec<(Packet, Packet)>;
+    type Answer1 = Result<usize, String>;
+    type Answer2 = usize;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        finish(input, parse_input(input))
+    }
+
+    /// Fails on a pair of equal packets, which is in neither order.
+    fn part_1(&self, pairs: &Vec<(Packet, Packet)>) -> Result<usize, String> {
+        pairs
+            .iter()
+            .enumerate()
+            .map(|(i, (packet1, packet2))| match packet1.cmp(packet2) {
+                std::cmp::Ordering::Less => Ok(i + 1),
+                std::cmp::Ordering::Equal => Err(format!("pair {} has two equal packets", i + 1)),
+                std::cmp::Ordering::Greater => Ok(0),
+            })
+            .sum::<Result<usize, String>>()
+    }
+
+    fn part_2(&self, pairs: &Vec<(Packet, Packet)>) -> usize {
+        let divider1 = Packet::List(vec![Packet::List(vec![Packet::Element(2)])]);
+        let divider2 = Packet::List(vec![Packet::List(vec![Packet::Element(6)])]);
+
+        let mut packets = pairs
+            .iter()
+            .flat_map(|(packet1, packet2)| [packet1, packet2])
+            .chain([&divider1, &divider2])
+            .collect::<Vec<&Packet>>();
+
+        packets.sort();
+
+        // Look for the dividers themselves, not for packets that merely equal them.
+        [&divider1, &divider2]
+            .iter()
+            .map(|&divider| {
+                let index = packets
+                    .iter()
+                    .position(|&packet| std::ptr::eq(packet, divider));
+                index.unwrap() + 1
+            })
+            .product::<usize>()
+    }
 }
 
 #[cfg(test)]
-mod tests {
+mod properties {
+    use std::cmp::Ordering;
+
+    use proptest::prelude::*;
+
     use super::*;
 
-    #[rustfmt::skip]
-    const INPUT: &str = concat!(
-        "[1,1,3,1,1]\n",
-        "[1,1,5,1,1]\n",
-        "\n",
-        "[[1],[2,3,4]]\n",
-        "[[1],4]\n",
-        "\n",
-        "[9]\n",
-        "[[8,7,6]]\n",
-        "\n",
-        "[[4,4],4,4]\n",
-        "[[4,4],4,4,4]\n",
-        "\n",
-        "[7,7,7,7]\n",
-        "[7,7,7]\n",
-        "\n",
-        "[]\n",
-        "[3]\n",
-        "\n",
-        "[[[]]]\n",
-        "[[]]\n",
-        "\n",
-        "[1,[2,[3,[4,[5,6,7]]]],8,9]\n",
-        "[1,[2,[3,[4,[5,6,0]]]],8,9]\n",
-    );
-
-    #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "13");
+    fn packet() -> impl Strategy<Value = Packet> {
+        (0..12_u32)
+            .prop_map(Packet::Element)
+            .prop_recursive(4, 32, 5, |inner| {
+                prop::collection::vec(inner, 0..5).prop_map(Packet::List)
+            })
+    }
+
+    /// Packets are only ever lists at the top level.
+    fn top_level_packet() -> impl Strategy<Value = Packet> {
+        prop::collection::vec(packet(), 0..5).prop_map(Packet::List)
+    }
+
+    /// The comparison exactly as the puzzle words it: `Some(true)` when the pair is in the
+    /// right order, `Some(false)` when it is not, and `None` when it cannot tell.
+    fn in_right_order(left: &Packet, right: &Packet) -> Option<bool> {
+        match (left, right) {
+            (Packet::Element(left), Packet::Element(right)) if left == right => None,
+            (Packet::Element(left), Packet::Element(right)) => Some(left < right),
+            (Packet::List(left), Packet::List(right)) => {
+                for (left, right) in left.iter().zip(right) {
+                    if let Some(ordered) = in_right_order(left, right) {
+                        return Some(ordered);
+                    }
+                }
+                (left.len() != right.len()).then_some(left.len() < right.len())
+            }
+            (Packet::Element(left), right) => {
+                in_right_order(&Packet::List(vec![Packet::Element(*left)]), right)
+            }
+            (left, Packet::Element(right)) => {
+                in_right_order(left, &Packet::List(vec![Packet::Element(*right)]))
+            }
+        }
     }
 
-    #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "140");
+    proptest! {
+        #[test]
+        fn orders_packets_like_the_puzzle(left in packet(), right in packet()) {
+            let expected = match in_right_order(&left, &right) {
+                Some(true) => Ordering::Less,
+                Some(false) => Ordering::Greater,
+                None => Ordering::Equal,
+            };
+
+            prop_assert_eq!(left.cmp(&right), expected);
+        }
+
+        #[test]
+        fn parses_rendered_packets(packet in top_level_packet()) {
+            let parsed: Packet = packet.to_string().parse().unwrap();
+
+            prop_assert_eq!(format!("{parsed:?}"), format!("{packet:?}"));
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serializes_like_the_signal(packet in top_level_packet()) {
+            let json = serde_json::to_string(&packet).unwrap();
+            prop_assert_eq!(&json, &packet.to_string());
+
+            let parsed: Packet = serde_json::from_str(&json).unwrap();
+            prop_assert_eq!(format!("{parsed:?}"), format!("{packet:?}"));
+        }
+
+        #[test]
+        fn solves_like_the_puzzle(
+            pairs in prop::collection::vec((top_level_packet(), top_level_packet()), 1..10),
+        ) {
+            let dividers = [
+                Packet::List(vec![Packet::List(vec![Packet::Element(2)])]),
+                Packet::List(vec![Packet::List(vec![Packet::Element(6)])]),
+            ];
+            let packets = || pairs.iter().flat_map(|(left, right)| [left, right]);
+            prop_assume!(pairs
+                .iter()
+                .all(|(left, right)| in_right_order(left, right).is_some()));
+            prop_assume!(packets().all(|packet| dividers
+                .iter()
+                .all(|divider| in_right_order(packet, divider).is_some())));
+
+            let input: Vec<String> = pairs
+                .iter()
+                .map(|(left, right)| format!("{left}\n{right}"))
+                .collect();
+            let parsed = Day13.parse(&(input.join("\n\n") + "\n")).unwrap();
+
+            let sum_of_ordered: usize = (1..)
+                .zip(&pairs)
+                .filter(|(_, (left, right))| in_right_order(left, right) == Some(true))
+                .map(|(index, _)| index)
+                .sum();
+            let position = |divider: &Packet, offset| {
+                packets()
+                    .filter(|packet| in_right_order(packet, divider) == Some(true))
+                    .count()
+                    + offset
+            };
+            let decoder_key = position(&dividers[0], 1) * position(&dividers[1], 2);
+
+            prop_assert_eq!(Day13.part_1(&parsed), Ok(sum_of_ordered));
+            prop_assert_eq!(Day13.part_2(&parsed), decoder_key);
+        }
     }
 }
+
+common::example_tests!(Day13, example: [part_1, part_2]);
@@ -1,51 +1,94 @@
 use std::collections::HashSet;
 
+use common::parse::{number, split_once};
+#[cfg(feature = "visualize")]
+use common::visual::{Color, Frame, Recorder, Visualize};
+#[cfg(feature = "visualize")]
+use common::Part;
+use common::{ParamError, ParseError, Solution};
 use itertools::Itertools;
 
-fn parse_input(input: &str) -> HashSet<(usize, usize)> {
-    input
-        .lines()
-        .flat_map(|line| {
-            line.split(" -> ")
-                .flat_map(|pair| pair.split_once(','))
-                .map(|(x, y)| (x.parse::<usize>().unwrap(), y.parse::<usize>().unwrap()))
+pub struct Day14 {
+    pub source: (i64, i64),
+}
+
+impl Default for Day14 {
+    fn default() -> Self {
+        Self { source: (500, 0) }
+    }
+}
+
+fn parse_path(input: &str, line: &str) -> Result<Vec<(i64, i64)>, ParseError> {
+    line.split(" -> ")
+        .map(|pair| {
+            let (x, y) = split_once(input, pair, ",")?;
+            let x = number::<u32>(input, x)?;
+            let y = number::<u32>(input, y)?;
+            Ok((x.into(), y.into()))
+        })
+        .collect()
+}
+
+fn parse_input(input: &str) -> Result<HashSet<(i64, i64)>, ParseError> {
+    if input.trim().is_empty() {
+        return Err(ParseError::at(input, input, "a rock path"));
+    }
+    let mut rocks = HashSet::new();
+    for line in input.trim_end().lines() {
+        let path = parse_path(input, line)?;
+        // A path of a single point is a single rock.
+        rocks.extend(path.iter().copied());
+        rocks.extend(
+            path.into_iter()
                 .tuple_windows()
                 .flat_map(|((a_x, a_y), (b_x, b_y))| {
                     let x_range = a_x.min(b_x)..=a_x.max(b_x);
                     let y_range = a_y.min(b_y)..=a_y.max(b_y);
                     x_range.cartesian_product(y_range)
-                })
-        })
-        .collect()
+                }),
+        );
+    }
+    Ok(rocks)
 }
 
-fn find_depth(rocks: &HashSet<(usize, usize)>) -> usize {
+fn find_depth(rocks: &HashSet<(i64, i64)>) -> i64 {
     rocks.iter().max_by(|(_, a), (_, b)| a.cmp(b)).unwrap().1
 }
 
-fn drop_sand(mut impassable: HashSet<(usize, usize)>, depth: usize) -> usize {
+/// Pours sand until it falls past `depth`, calling `on_rest` with each grain that comes to rest.
+fn drop_sand(
+    mut impassable: HashSet<(i64, i64)>,
+    depth: i64,
+    source: (i64, i64),
+    mut on_rest: impl FnMut((i64, i64)),
+) -> usize {
     let mut sand_dropped = 0;
     loop {
-        let mut sand = (500, 0);
+        let mut sand = source;
+        // Rocks shaped like a bowl can fill up to the source before sand overflows them.
+        if impassable.contains(&sand) {
+            break;
+        }
         loop {
             sand.1 += 1;
             if sand.1 > depth {
                 break;
             }
-            if impassable.get(&sand).is_none() {
+            if !impassable.contains(&sand) {
                 continue;
             }
             sand.0 -= 1;
-            if impassable.get(&sand).is_none() {
+            if !impassable.contains(&sand) {
                 continue;
             }
             sand.0 += 2;
-            if impassable.get(&sand).is_none() {
+            if !impassable.contains(&sand) {
                 continue;
             }
             sand.0 -= 1;
             sand.1 -= 1;
             impassable.insert(sand);
+            on_rest(sand);
             sand_dropped += 1;
             break;
         }
@@ -56,34 +99,43 @@ fn drop_sand(mut impassable: HashSet<(usize, usize)>, depth: usize) -> usize {
     sand_dropped
 }
 
-fn drop_sand_floored(mut impassable: HashSet<(usize, usize)>, depth: usize) -> usize {
+/// Pours sand onto a floor below `depth` until it buries the source, calling `on_rest` with each
+/// grain that comes to rest.
+fn drop_sand_floored(
+    mut impassable: HashSet<(i64, i64)>,
+    depth: i64,
+    source: (i64, i64),
+    mut on_rest: impl FnMut((i64, i64)),
+) -> usize {
     let mut sand_dropped = 0;
     loop {
-        let mut sand = (500, 0);
-        if impassable.get(&sand).is_some() {
+        let mut sand = source;
+        if impassable.contains(&sand) {
             break;
         }
         loop {
             if sand.1 > depth {
                 impassable.insert(sand);
+                on_rest(sand);
                 sand_dropped += 1;
                 break;
             }
             sand.1 += 1;
-            if impassable.get(&sand).is_none() {
+            if !impassable.contains(&sand) {
                 continue;
             }
             sand.0 -= 1;
-            if impassable.get(&sand).is_none() {
+            if !impassable.contains(&sand) {
                 continue;
             }
             sand.0 += 2;
-            if impassable.get(&sand).is_none() {
+            if !impassable.contains(&sand) {
                 continue;
             }
             sand.0 -= 1;
             sand.1 -= 1;
             impassable.insert(sand);
+            on_rest(sand);
             sand_dropped += 1;
             break;
         }
@@ -91,37 +143,188 @@ fn drop_sand_floored(mut impassable: HashSet<(usize, usize)>, depth: usize) -> u
     sand_dropped
 }
 
-pub fn part_1(input: &str) -> String {
-    let rocks = parse_input(input);
-    let depth = find_depth(&rocks);
-    let sand_dropped = drop_sand(rocks, depth);
-    sand_dropped.to_string()
+impl Solution for Day14 {
+    type Parsed<'a> = HashSet<(i64, i64)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(&self, rocks: &HashSet<(i64, i64)>) -> usize {
+        let depth = find_depth(rocks);
+        drop_sand(rocks.clone(), depth, self.source, |_| {})
+    }
+
+    fn part_2(&self, rocks: &HashSet<(i64, i64)>) -> usize {
+        let depth = find_depth(rocks);
+        drop_sand_floored(rocks.clone(), depth, self.source, |_| {})
+    }
+
+    /// `source` takes the point sand pours from as `x,y`.
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), ParamError> {
+        match name {
+            "source" => {
+                let source = value
+                    .split_once(',')
+                    .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)));
+                self.source = source.ok_or_else(|| ParamError::invalid(name, value))?;
+            }
+            _ => return Err(ParamError::unknown(name)),
+        }
+        Ok(())
+    }
 }
 
-pub fn part_2(input: &str) -> String {
-    let rocks = parse_input(input);
-    let depth = find_depth(&rocks);
-    let sand_dropped = drop_sand_floored(rocks, depth);
-    sand_dropped.to_string()
+#[cfg(feature = "visualize")]
+const PALETTE: &[Color] = &[
+    Color::new(' ', [20, 20, 28]),
+    Color::new('#', [120, 110, 100]),
+    Color::new('o', [225, 190, 110]),
+    Color::new('+', [240, 90, 60]),
+];
+
+/// Draws the cave from the source down to the lowest rock, or to the floor in part 2, adding a
+/// frame each time a grain of sand comes to rest.
+#[cfg(feature = "visualize")]
+impl Visualize for Day14 {
+    fn visualize(&self, rocks: &HashSet<(i64, i64)>, part: Part, recorder: &mut Recorder) {
+        let depth = find_depth(rocks);
+        let floor = depth + 2;
+        let xs = rocks.iter().map(|&(x, _)| x).chain([self.source.0]);
+        let (mut min_x, mut max_x) = (xs.clone().min().unwrap(), xs.max().unwrap());
+        let min_y = self.source.1.min(0);
+        let max_y = match part {
+            Part::One => depth,
+            Part::Two => {
+                // Sand piles up into a triangle under the source, as wide as it is tall.
+                min_x = min_x.min(self.source.0 - (floor - self.source.1));
+                max_x = max_x.max(self.source.0 + (floor - self.source.1));
+                floor
+            }
+        };
+        let cell = |(x, y): (i64, i64)| ((x - min_x) as usize, (y - min_y) as usize);
+
+        let mut frame = Frame::new(
+            PALETTE,
+            (max_x - min_x + 1) as usize,
+            (max_y - min_y + 1) as usize,
+        );
+        for &rock in rocks {
+            let (x, y) = cell(rock);
+            frame.set(x, y, 1);
+        }
+        if part == Part::Two {
+            let (_, y) = cell((min_x, floor));
+            for x in 0..frame.width() {
+                frame.set(x, y, 1);
+            }
+        }
+        let source = cell(self.source);
+        frame.set(source.0, source.1, 3);
+        recorder.frame(|| frame.clone());
+
+        let mut on_rest = |sand| {
+            let (x, y) = cell(sand);
+            frame.set(x, y, 2);
+            recorder.step(|| frame.clone());
+        };
+        match part {
+            Part::One => drop_sand(rocks.clone(), depth, self.source, &mut on_rest),
+            Part::Two => drop_sand_floored(rocks.clone(), depth, self.source, &mut on_rest),
+        };
+        recorder.frame(|| frame);
+    }
 }
 
 #[cfg(test)]
-mod tests {
+mod properties {
+    use proptest::prelude::*;
+
     use super::*;
 
-    #[rustfmt::skip]
-    const INPUT: &str = concat!(
-        "498,4 -> 498,6 -> 496,6\n",
-        "503,4 -> 502,4 -> 502,9 -> 494,9\n",
-    );
+    /// A rock path of axis-aligned segments starting near the default source.
+    fn path() -> impl Strategy<Value = Vec<(usize, usize)>> {
+        let step = (any::<bool>(), -6..=6_isize);
+        (
+            (490..=510_usize, 1..=12_usize),
+            prop::collection::vec(step, 1..4),
+        )
+            .prop_map(|(start, steps)| {
+                let mut path = vec![start];
+                for (horizontal, distance) in steps {
+                    let (x, y) = *path.last().unwrap();
+                    path.push(if horizontal {
+                        (x.saturating_add_signed(distance).clamp(490, 510), y)
+                    } else {
+                        (x, y.saturating_add_signed(distance).clamp(1, 12))
+                    });
+                }
+                path
+            })
+    }
 
-    #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "24");
+    fn render(paths: &[Vec<(usize, usize)>]) -> String {
+        paths
+            .iter()
+            .map(|path| {
+                let points: Vec<String> = path.iter().map(|(x, y)| format!("{x},{y}")).collect();
+                points.join(" -> ") + "\n"
+            })
+            .collect()
     }
 
-    #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "93");
+    /// Pours sand one grain at a time onto a grid wide enough for any pile, stopping when a
+    /// grain would fall past `floor`, or when the source is buried if `floor` is solid.
+    fn pour(paths: &[Vec<(usize, usize)>], solid_floor: bool) -> usize {
+        let depth = paths.iter().flatten().map(|&(_, y)| y).max().unwrap();
+        let floor = depth + 2;
+        // Rocks stay within 490..=510 and sand spreads at most `floor` either side of 500.
+        let offset = 490 - floor - 1;
+        let mut blocked = vec![vec![false; 2 * floor + 23]; floor + 1];
+        for path in paths {
+            for pair in path.windows(2) {
+                let ((a_x, a_y), (b_x, b_y)) = (pair[0], pair[1]);
+                for row in &mut blocked[a_y.min(b_y)..=a_y.max(b_y)] {
+                    row[a_x.min(b_x) - offset..=a_x.max(b_x) - offset].fill(true);
+                }
+            }
+        }
+        if solid_floor {
+            blocked[floor].fill(true);
+        }
+
+        let mut grains = 0;
+        while !blocked[0][500 - offset] {
+            let (mut x, mut y) = (500 - offset, 0);
+            loop {
+                if y + 1 == floor && !solid_floor {
+                    return grains;
+                }
+                match [x, x - 1, x + 1]
+                    .into_iter()
+                    .find(|&next| !blocked[y + 1][next])
+                {
+                    Some(next) => (x, y) = (next, y + 1),
+                    None => break,
+                }
+            }
+            blocked[y][x] = true;
+            grains += 1;
+        }
+        grains
+    }
+
+    proptest! {
+        #[test]
+        fn pours_like_a_grid_simulation(paths in prop::collection::vec(path(), 1..5)) {
+            let rocks = Day14::default().parse(&render(&paths)).unwrap();
+
+            prop_assert_eq!(Day14::default().part_1(&rocks), pour(&paths, false));
+            prop_assert_eq!(Day14::default().part_2(&rocks), pour(&paths, true));
+        }
     }
 }
+
+common::example_tests!(Day14, example: [part_1, part_2]);
@@ -1,19 +1,58 @@
 use std::collections::HashSet;
+use std::str::FromStr;
 
-use nom::{
-    bytes::complete::tag,
-    character::complete::{self, newline},
-    multi::separated_list0,
-    IResult,
-};
-
-#[derive(Debug)]
-struct Sensor {
-    position: (i32, i32),
-    beacon: (i32, i32),
+use common::nom::{finish, separated_to_end, tag, IResult};
+use common::{ParamError, ParseError, Solution};
+use nom::character::complete;
+
+pub struct Day15 {
+    pub row_index: i32,
+    pub max_coord: i32,
+}
+
+impl Default for Day15 {
+    fn default() -> Self {
+        Self {
+            row_index: 2_000_000,
+            max_coord: 4_000_000,
+        }
+    }
+}
+
+/// A sensor and the closest beacon it detected, as `(x, y)` positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sensor {
+    position: (i64, i64),
+    beacon: (i64, i64),
+}
+
+impl Sensor {
+    pub fn position(&self) -> (i64, i64) {
+        self.position
+    }
+
+    pub fn beacon(&self) -> (i64, i64) {
+        self.beacon
+    }
+
+    /// The Manhattan distance to the beacon. No other beacon can be this close to the sensor.
+    pub fn radius(&self) -> i64 {
+        get_manhattan_distance(self.position, self.beacon)
+    }
+}
+
+impl FromStr for Sensor {
+    type Err = ParseError;
+
+    /// Parses one line of the report, e.g.
+    /// `Sensor at x=2, y=18: closest beacon is at x=-2, y=15`.
+    fn from_str(line: &str) -> Result<Self, ParseError> {
+        finish(line, parse_sensor(line))
+    }
 }
 
-fn parse_sensor(input: &str) -> IResult<&str, Sensor> {
+fn parse_sensor(input: &str) -> IResult<'_, Sensor> {
     let (input, _) = tag("Sensor at x=")(input)?;
     let (input, sensor_x) = complete::i32(input)?;
     let (input, _) = tag(", y=")(input)?;
@@ -25,45 +64,47 @@ fn parse_sensor(input: &str) -> IResult<&str, Sensor> {
     Ok((
         input,
         Sensor {
-            position: (sensor_x, sensor_y),
-            beacon: (beacon_x, beacon_y),
+            position: (sensor_x.into(), sensor_y.into()),
+            beacon: (beacon_x.into(), beacon_y.into()),
         },
     ))
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Sensor>> {
-    separated_list0(newline, parse_sensor)(input)
+fn parse_input(input: &str) -> IResult<'_, Vec<Sensor>> {
+    separated_to_end("\n", parse_sensor)(input)
 }
 
-fn get_blocked_in_row(sensors: Vec<Sensor>, row_index: i32) -> HashSet<i32> {
-    sensors.iter().fold(
+/// Positions in the row that some sensor rules out, not counting beacons already in the row.
+fn get_blocked_in_row(sensors: &[Sensor], row_index: i64) -> HashSet<i64> {
+    let mut row = sensors.iter().fold(
         HashSet::new(),
         |mut row,
-         Sensor {
+         sensor @ Sensor {
              position: (position_x, position_y),
-             beacon: (beacon_x, beacon_y),
+             ..
          }| {
-            let manhattan_distance =
-                get_manhattan_distance((*position_x, *position_y), (*beacon_x, *beacon_y));
+            let manhattan_distance = sensor.radius();
             let offset_from_row = position_y.max(&row_index) - position_y.min(&row_index);
             let blocked_radius = manhattan_distance - offset_from_row;
-            for x in (position_x - blocked_radius)..(position_x + blocked_radius) {
+            for x in (position_x - blocked_radius)..=(position_x + blocked_radius) {
                 row.insert(x);
             }
             row
         },
-    )
+    );
+    for sensor in sensors {
+        if sensor.beacon.1 == row_index {
+            row.remove(&sensor.beacon.0);
+        }
+    }
+    row
 }
 
-fn search_for_gap(sensors: Vec<Sensor>, max_coord: i32) -> (i32, i32) {
-    let sensors_with_distance: Vec<(&Sensor, i32)> = sensors
+/// The first position within `0..=max_coord` that no sensor covers, scanning row by row.
+fn search_for_gap(sensors: &[Sensor], max_coord: i64) -> Option<(i64, i64)> {
+    let sensors_with_distance: Vec<(&Sensor, i64)> = sensors
         .iter()
-        .map(|sensor| {
-            (
-                sensor,
-                get_manhattan_distance(sensor.position, sensor.beacon),
-            )
-        })
+        .map(|sensor| (sensor, sensor.radius()))
         .collect();
 
     for y in 0..=max_coord {
@@ -90,63 +131,147 @@ fn search_for_gap(sensors: Vec<Sensor>, max_coord: i32) -> (i32, i32) {
             ) {
                 x = new_x;
             } else {
-                return (x, y);
+                return Some((x, y));
             }
         }
     }
-    panic!()
+    None
 }
 
 fn get_manhattan_distance(
-    (position_x, position_y): (i32, i32),
-    (beacon_x, beacon_y): (i32, i32),
-) -> i32 {
+    (position_x, position_y): (i64, i64),
+    (beacon_x, beacon_y): (i64, i64),
+) -> i64 {
     let x_distance = position_x.max(beacon_x) - position_x.min(beacon_x);
     let y_distance = position_y.max(beacon_y) - position_y.min(beacon_y);
     x_distance + y_distance
 }
 
-pub fn part_1(input: &str, row_index: i32) -> String {
-    let (_, sensors) = parse_input(input).unwrap();
-    let blocked_in_row = get_blocked_in_row(sensors, row_index);
-    blocked_in_row.len().to_string()
-}
+impl Solution for Day15 {
+    type Parsed<'a> = Vec<Sensor>;
+    type Answer1 = usize;
+    type Answer2 = Result<i64, String>;
 
-pub fn part_2(input: &str, max_coord: i32) -> String {
-    let (_, sensors) = parse_input(input).unwrap();
-    let (x, y) = search_for_gap(sensors, max_coord);
-    (x as u64 * 4_000_000 + y as u64).to_string()
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        finish(input, parse_input(input))
+    }
+
+    fn part_1(&self, sensors: &Vec<Sensor>) -> usize {
+        let blocked_in_row = get_blocked_in_row(sensors, self.row_index.into());
+        blocked_in_row.len()
+    }
+
+    fn part_2(&self, sensors: &Vec<Sensor>) -> Result<i64, String> {
+        search_for_gap(sensors, self.max_coord.into())
+            .map(|(x, y)| x * 4_000_000 + y)
+            .ok_or_else(|| String::from("no position is left for the distress beacon"))
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), ParamError> {
+        match name {
+            "row" => self.row_index = ParamError::parse_value(name, value)?,
+            "max-coord" => self.max_coord = ParamError::parse_value(name, value)?,
+            _ => return Err(ParamError::unknown(name)),
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[rustfmt::skip]
-    const INPUT: &str = concat!(
-        "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\n",
-        "Sensor at x=9, y=16: closest beacon is at x=10, y=16\n",
-        "Sensor at x=13, y=2: closest beacon is at x=15, y=3\n",
-        "Sensor at x=12, y=14: closest beacon is at x=10, y=16\n",
-        "Sensor at x=10, y=20: closest beacon is at x=10, y=16\n",
-        "Sensor at x=14, y=17: closest beacon is at x=10, y=16\n",
-        "Sensor at x=8, y=7: closest beacon is at x=2, y=10\n",
-        "Sensor at x=2, y=0: closest beacon is at x=2, y=10\n",
-        "Sensor at x=0, y=11: closest beacon is at x=2, y=10\n",
-        "Sensor at x=20, y=14: closest beacon is at x=25, y=17\n",
-        "Sensor at x=17, y=20: closest beacon is at x=21, y=22\n",
-        "Sensor at x=16, y=7: closest beacon is at x=15, y=3\n",
-        "Sensor at x=14, y=3: closest beacon is at x=15, y=3\n",
-        "Sensor at x=20, y=1: closest beacon is at x=15, y=3\n",
-    );
+    #[test]
+    fn parses_a_sensor() {
+        let sensor: Sensor = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15"
+            .parse()
+            .unwrap();
+
+        assert_eq!(sensor.position(), (2, 18));
+        assert_eq!(sensor.beacon(), (-2, 15));
+        assert_eq!(sensor.radius(), 7);
+    }
 
     #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT, 10), "26");
+    fn rejects_a_sensor_without_a_beacon() {
+        let error = "Sensor at x=2, y=18: closest beacon is at x=-2"
+            .parse::<Sensor>()
+            .unwrap_err();
+
+        assert_eq!((error.line(), error.column()), (1, 47));
+        assert_eq!(error.expected(), "\", y=\"");
     }
 
     #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT, 20), "56000011");
+    fn reports_an_error_when_no_position_is_left() {
+        let day = Day15 {
+            row_index: 10,
+            max_coord: 20,
+        };
+        let sensors = day
+            .parse("Sensor at x=10, y=10: closest beacon is at x=-20, y=10\n")
+            .unwrap();
+
+        assert_eq!(
+            day.part_2(&sensors),
+            Err(String::from("no position is left for the distress beacon"))
+        );
     }
 }
+
+#[cfg(test)]
+mod properties {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const MAX_COORD: i64 = 20;
+
+    /// A sensor's position and that of its closest beacon.
+    type Report = ((i64, i64), (i64, i64));
+
+    fn sensors() -> impl Strategy<Value = Vec<Report>> {
+        let point = || (-5..=MAX_COORD + 5, -5..=MAX_COORD + 5);
+        prop::collection::vec((point(), point()), 1..8)
+    }
+
+    fn render(sensors: &[Report]) -> String {
+        sensors
+            .iter()
+            .map(|((x, y), (beacon_x, beacon_y))| {
+                format!("Sensor at x={x}, y={y}: closest beacon is at x={beacon_x}, y={beacon_y}\n")
+            })
+            .collect()
+    }
+
+    fn covered(sensors: &[Report], point: (i64, i64)) -> bool {
+        sensors.iter().any(|&(sensor, beacon)| {
+            get_manhattan_distance(sensor, point) <= get_manhattan_distance(sensor, beacon)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn counts_blocked_positions_like_a_scan(sensors in sensors(), row in 0..=MAX_COORD) {
+            let parsed = Day15::default().parse(&render(&sensors)).unwrap();
+            let expected = (-100..=100)
+                .filter(|&x| covered(&sensors, (x, row)))
+                .filter(|&x| !sensors.iter().any(|&(_, beacon)| beacon == (x, row)))
+                .count();
+
+            prop_assert_eq!(get_blocked_in_row(&parsed, row).len(), expected);
+        }
+
+        #[test]
+        fn finds_the_first_gap_like_a_scan(sensors in sensors()) {
+            let parsed = Day15::default().parse(&render(&sensors)).unwrap();
+            let gap = (0..=MAX_COORD)
+                .flat_map(|y| (0..=MAX_COORD).map(move |x| (x, y)))
+                .find(|&point| !covered(&sensors, point));
+
+            prop_assert_eq!(search_for_gap(&parsed, MAX_COORD), gap);
+        }
+    }
+}
+
+common::example_tests!(Day15, example: [part_1, part_2]);
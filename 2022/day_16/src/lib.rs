@@ -1,26 +1,97 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
+use common::nom::{finish, separated_to_end, tag, IResult};
+use common::{Answer, ParseError, Solution};
 use nom::{
-    bytes::complete::tag,
-    character::complete::{self, alpha0, newline},
-    multi::separated_list0,
+    branch::alt,
+    character::complete::{self, alpha1},
+    multi::separated_list1,
     sequence::preceded,
-    IResult, branch::alt,
 };
+use tracing::{debug, trace};
 
-#[derive(Debug)]
-struct Valve<'a> {
+#[derive(Default)]
+pub struct Day16;
+
+/// A scanned valve, with the names of the valves its tunnels lead to. With the `serde` feature,
+/// [`OwnedValve`] is the version that can be deserialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Valve<'a> {
     name: &'a str,
     flow_rate: u32,
     tunnels: Vec<&'a str>,
 }
 
-fn parse_valve(input: &str) -> IResult<&str, Valve> {
-    let (input, name) = preceded(tag("Valve "), alpha0)(input)?;
+impl<'a> Valve<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Pressure released per minute once the valve is open.
+    pub fn flow_rate(&self) -> u32 {
+        self.flow_rate
+    }
+
+    pub fn tunnels(&self) -> &[&'a str] {
+        &self.tunnels
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Valve<'a> {
+    type Error = ParseError;
+
+    /// Parses one line of the scan, e.g.
+    /// `Valve BB has flow rate=13; tunnels lead to valves CC, AA`.
+    fn try_from(line: &'a str) -> Result<Self, ParseError> {
+        finish(line, parse_valve(line))
+    }
+}
+
+/// [`Valve`] with the names it borrows from the scan copied.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OwnedValve {
+    pub name: String,
+    pub flow_rate: u32,
+    pub tunnels: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+impl From<&Valve<'_>> for OwnedValve {
+    fn from(valve: &Valve<'_>) -> Self {
+        Self {
+            name: valve.name.to_string(),
+            flow_rate: valve.flow_rate,
+            tunnels: valve
+                .tunnels
+                .iter()
+                .map(|tunnel| tunnel.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> From<&'a OwnedValve> for Valve<'a> {
+    fn from(valve: &'a OwnedValve) -> Self {
+        Self {
+            name: &valve.name,
+            flow_rate: valve.flow_rate,
+            tunnels: valve.tunnels.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+fn parse_valve(input: &str) -> IResult<'_, Valve<'_>> {
+    let (input, name) = preceded(tag("Valve "), alpha1)(input)?;
     let (input, flow_rate) = preceded(tag(" has flow rate="), complete::u32)(input)?;
     let (input, tunnels) = preceded(
-        alt((tag("; tunnels lead to valves "), tag("; tunnel leads to valve "))),
-        separated_list0(tag(", "), alpha0),
+        alt((
+            tag("; tunnels lead to valves "),
+            tag("; tunnel leads to valve "),
+        )),
+        separated_list1(tag(", "), alpha1),
     )(input)?;
     Ok((
         input,
@@ -32,74 +103,217 @@ fn parse_valve(input: &str) -> IResult<&str, Valve> {
     ))
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Valve>> {
-    separated_list0(newline, parse_valve)(input)
+fn parse_input(input: &str) -> IResult<'_, Vec<Valve<'_>>> {
+    separated_to_end("\n", parse_valve)(input)
 }
 
-fn generate_hashmap<'a>(valves: &'a [Valve<'a>]) -> HashMap<&'a str, &Valve<'a>> {
+/// Checks that every tunnel leads to a valve of the scan.
+fn check_tunnels(input: &str, valves: &[Valve]) -> Result<(), ParseError> {
+    for tunnel in valves.iter().flat_map(|valve| &valve.tunnels) {
+        if !valves.iter().any(|valve| valve.name == *tunnel) {
+            return Err(ParseError::at(input, tunnel, "the name of a scanned valve"));
+        }
+    }
+    Ok(())
+}
+
+fn generate_hashmap<'a>(valves: &'a [Valve<'a>]) -> HashMap<&'a str, &'a Valve<'a>> {
     valves.iter().map(|valve| (valve.name, valve)).collect()
 }
 
-fn find_best_path(valves: &HashMap<&str, &Valve>, keys: Vec<&str>) -> u32 {
-    if keys.len() > 15 {
-        return 0;
+fn generate_distances<'a>(
+    valves: &HashMap<&'a str, &'a Valve<'a>>,
+) -> HashMap<(&'a str, &'a str), u32> {
+    let mut distances = HashMap::new();
+    for &start in valves.keys() {
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((key, distance)) = queue.pop_front() {
+            if distances.contains_key(&(start, key)) {
+                continue;
+            }
+            distances.insert((start, key), distance);
+            for tunnel in valves.get(key).unwrap().tunnels.iter() {
+                queue.push_back((tunnel, distance + 1));
+            }
+        }
     }
+    distances
+}
+
+/// How far [`find_best_path`] went, reported once the search is over.
+#[derive(Default)]
+struct SearchStats {
+    paths: u64,
+    deepest: usize,
+}
 
-    let remaining = 30 - keys.len() as u32 * 2;
+fn find_best_path(
+    valves: &HashMap<&str, &Valve>,
+    distances: &HashMap<(&str, &str), u32>,
+    keys: Vec<&str>,
+    remaining: u32,
+    stats: &mut SearchStats,
+) -> u32 {
     let key = keys.last().unwrap();
-    let current_valve = valves.get(key).unwrap();
+    // The path starts at `AA` without opening it.
+    let depth = keys.len() - 1;
+    trace!(depth, valve = *key, remaining, "searching");
+    stats.paths += 1;
+    stats.deepest = stats.deepest.max(depth);
 
-    let best_path_amount = current_valve
-        .tunnels
-        .iter()
-        .map(|key| find_best_path(valves, [keys.clone(), vec![key]].concat()))
+    valves
+        .values()
+        .filter(|valve| valve.flow_rate > 0 && !keys.contains(&valve.name))
+        .filter_map(|valve| {
+            let cost = distances.get(&(*key, valve.name))? + 1;
+            let remaining = remaining.checked_sub(cost)?;
+            let best_path_amount = find_best_path(
+                valves,
+                distances,
+                [keys.clone(), vec![valve.name]].concat(),
+                remaining,
+                stats,
+            );
+            Some(best_path_amount + valve.flow_rate * remaining)
+        })
         .max()
-        .unwrap();
+        .unwrap_or(0)
+}
+
+impl Solution for Day16 {
+    type Parsed<'a> = Vec<Valve<'a>>;
+    type Answer1 = u32;
+    type Answer2 = Answer;
 
-    if keys[..keys.len() - 1].contains(key) {
-        best_path_amount
-    } else {
-        best_path_amount + current_valve.flow_rate * remaining
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        let valves = finish(input, parse_input(input))?;
+        check_tunnels(input, &valves)?;
+        Ok(valves)
     }
-}
 
-pub fn part_1(input: &str) -> String {
-    let (_, valves) = parse_input(input).unwrap();
-    let valves = generate_hashmap(&valves);
-    let best_path_amount = find_best_path(&valves, vec!["AA"]);
-    best_path_amount.to_string()
-}
+    fn part_1(&self, valves: &Vec<Valve<'_>>) -> u32 {
+        let valves = generate_hashmap(valves);
+        let distances = generate_distances(&valves);
+        let mut stats = SearchStats::default();
+        let pressure = find_best_path(&valves, &distances, vec!["AA"], 30, &mut stats);
+        debug!(paths = stats.paths, deepest = stats.deepest, "search over");
+        pressure
+    }
 
-pub fn part_2(input: &str) -> String {
-    "part2".to_string()
+    fn part_2(&self, _valves: &Vec<Valve<'_>>) -> Answer {
+        Answer::Unsolved
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[rustfmt::skip]
-    const INPUT: &str = concat!(
-        "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB\n",
-        "Valve BB has flow rate=13; tunnels lead to valves CC, AA\n",
-        "Valve CC has flow rate=2; tunnels lead to valves DD, BB\n",
-        "Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE\n",
-        "Valve EE has flow rate=3; tunnels lead to valves FF, DD\n",
-        "Valve FF has flow rate=0; tunnels lead to valves EE, GG\n",
-        "Valve GG has flow rate=0; tunnels lead to valves FF, HH\n",
-        "Valve HH has flow rate=22; tunnel leads to valve GG\n",
-        "Valve II has flow rate=0; tunnels lead to valves AA, JJ\n",
-        "Valve JJ has flow rate=21; tunnel leads to valve II\n",
-    );
-
     #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "1651");
+    fn parses_one_valve() {
+        let valve = Valve::try_from("Valve BB has flow rate=13; tunnels lead to valves CC, AA");
+        let valve = valve.unwrap();
+
+        assert_eq!(valve.name(), "BB");
+        assert_eq!(valve.flow_rate(), 13);
+        assert_eq!(valve.tunnels(), ["CC", "AA"]);
+        assert!(Valve::try_from("Valve BB has flow rate=13").is_err());
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    #[ignore]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "part2");
+    fn round_trips_through_owned_valves() {
+        let valve = Valve::try_from("Valve AA has flow rate=0; tunnel leads to valve BB").unwrap();
+        let json = serde_json::to_string(&valve).unwrap();
+        assert_eq!(json, r#"{"name":"AA","flow_rate":0,"tunnels":["BB"]}"#);
+
+        let owned: OwnedValve = serde_json::from_str(&json).unwrap();
+        assert_eq!(owned, OwnedValve::from(&valve));
+        assert_eq!(Valve::from(&owned), valve);
     }
 }
+
+#[cfg(test)]
+mod properties {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const NAMES: [&str; 6] = ["AA", "BB", "CC", "DD", "EE", "FF"];
+
+    /// Flow rates and tunnel targets by index into [`NAMES`]. Like in the puzzle, `AA` has no
+    /// flow.
+    fn valves() -> impl Strategy<Value = Vec<(u32, Vec<usize>)>> {
+        (2..=NAMES.len()).prop_flat_map(|count| {
+            let valve = (
+                0..25_u32,
+                prop::sample::subsequence((0..count).collect::<Vec<_>>(), 1..count),
+            );
+            prop::collection::vec(valve, count).prop_map(|mut valves| {
+                valves[0].0 = 0;
+                valves
+            })
+        })
+    }
+
+    fn render(valves: &[(u32, Vec<usize>)]) -> String {
+        valves
+            .iter()
+            .enumerate()
+            .map(|(i, (flow_rate, tunnels))| {
+                let tunnels: Vec<&str> = tunnels.iter().map(|&tunnel| NAMES[tunnel]).collect();
+                let lead = if tunnels.len() == 1 {
+                    "tunnel leads to valve"
+                } else {
+                    "tunnels lead to valves"
+                };
+                format!(
+                    "Valve {} has flow rate={flow_rate}; {lead} {}\n",
+                    NAMES[i],
+                    tunnels.join(", ")
+                )
+            })
+            .collect()
+    }
+
+    /// Most pressure released from `valve` with `remaining` minutes left and the valves in
+    /// `open` already open, trying every move minute by minute.
+    fn most_pressure(
+        valves: &[(u32, Vec<usize>)],
+        valve: usize,
+        remaining: u32,
+        open: u32,
+        memo: &mut HashMap<(usize, u32, u32), u32>,
+    ) -> u32 {
+        if remaining == 0 {
+            return 0;
+        }
+        if let Some(&pressure) = memo.get(&(valve, remaining, open)) {
+            return pressure;
+        }
+        let (flow_rate, tunnels) = &valves[valve];
+        let mut best = 0;
+        if *flow_rate > 0 && open & 1 << valve == 0 {
+            let rest = most_pressure(valves, valve, remaining - 1, open | 1 << valve, memo);
+            best = best.max(flow_rate * (remaining - 1) + rest);
+        }
+        for &tunnel in tunnels {
+            best = best.max(most_pressure(valves, tunnel, remaining - 1, open, memo));
+        }
+        memo.insert((valve, remaining, open), best);
+        best
+    }
+
+    proptest! {
+        #[test]
+        fn releases_as_much_as_an_exhaustive_search(valves in valves()) {
+            let input = render(&valves);
+            let parsed = Day16.parse(&input).unwrap();
+            let expected = most_pressure(&valves, 0, 30, 0, &mut HashMap::new());
+
+            prop_assert_eq!(Day16.part_1(&parsed), expected);
+        }
+    }
+}
+
+common::example_tests!(Day16, example: [part_1, #[ignore = "not solved yet"] part_2]);
@@ -1,4 +1,16 @@
 use std::collections::HashSet;
+use std::str::FromStr;
+
+use common::parse::all_chars;
+#[cfg(feature = "visualize")]
+use common::visual::{Color, Frame, Recorder, Visualize};
+#[cfg(feature = "visualize")]
+use common::Part;
+use common::{Answer, ParseError, Solution};
+use tracing::trace;
+
+#[derive(Default)]
+pub struct Day17;
 
 type Rock = Vec<(u32, u32)>;
 
@@ -7,91 +19,213 @@ enum EndState {
     Falling(Rock),
 }
 
-enum Jet {
+/// Which way a jet of hot gas pushes the falling rock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jet {
     Left,
     Right,
 }
 
+impl FromStr for Jet {
+    type Err = ParseError;
+
+    /// Parses one jet of the pattern, `<` or `>`.
+    fn from_str(jet: &str) -> Result<Self, ParseError> {
+        match jet {
+            "<" => Ok(Jet::Left),
+            ">" => Ok(Jet::Right),
+            _ => Err(ParseError::at(jet, jet, "'<' or '>'")),
+        }
+    }
+}
+
 fn generate_rock(shape_number: usize, height: u32) -> Rock {
     match shape_number {
         0 => vec![(3, height), (4, height), (5, height), (6, height)],
-        1 => vec![(4, height), (3, height+1), (4, height+1), (5, height+1), (4, height+2)],
-        2 => vec![(3, height), (4, height), (5, height), (5, height+1), (5, height+2)],
-        3 => vec![(3, height), (3, height+1), (3, height+2), (3, height+3)],
-        4 => vec![(3, height), (4, height), (3, height+1), (4, height+1)],
+        1 => vec![
+            (4, height),
+            (3, height + 1),
+            (4, height + 1),
+            (5, height + 1),
+            (4, height + 2),
+        ],
+        2 => vec![
+            (3, height),
+            (4, height),
+            (5, height),
+            (5, height + 1),
+            (5, height + 2),
+        ],
+        3 => vec![
+            (3, height),
+            (3, height + 1),
+            (3, height + 2),
+            (3, height + 3),
+        ],
+        4 => vec![(3, height), (4, height), (3, height + 1), (4, height + 1)],
         _ => panic!(),
     }
 }
 
-fn jet_step(board: &HashSet<(u32, u32)>, rock: Rock, direction: Jet) -> Rock {
+fn jet_step(board: &HashSet<(u32, u32)>, rock: Rock, direction: &Jet) -> Rock {
     let new_rock: Vec<(u32, u32)> = match direction {
-        Jet::Left => rock.iter().map(|(x, y)| (x-1, *y)).collect(),
-        Jet::Right => rock.iter().map(|(x, y)| (x+1, *y)).collect(),
+        Jet::Left => rock.iter().map(|(x, y)| (x - 1, *y)).collect(),
+        Jet::Right => rock.iter().map(|(x, y)| (x + 1, *y)).collect(),
     };
-    if new_rock.iter().any(|piece| board.contains(piece) || piece.0 == 0 || piece.0 == 8) {
+    if new_rock
+        .iter()
+        .any(|piece| board.contains(piece) || piece.0 == 0 || piece.0 == 8)
+    {
         return rock;
     }
     new_rock
 }
 
 fn fall_step(board: &mut HashSet<(u32, u32)>, rock: Rock) -> EndState {
-    let new_rock: Vec<(u32, u32)> = rock.iter().map(|(x, y)| (*x, y-1)).collect();
-    if new_rock.iter().any(|piece| board.contains(piece) || piece.1 == 0) {
-        rock.iter().for_each(|piece| { board.insert(*piece); });
+    let new_rock: Vec<(u32, u32)> = rock.iter().map(|(x, y)| (*x, y - 1)).collect();
+    if new_rock
+        .iter()
+        .any(|piece| board.contains(piece) || piece.1 == 0)
+    {
+        rock.iter().for_each(|piece| {
+            board.insert(*piece);
+        });
         return EndState::Landed(*rock.iter().map(|(_, y)| y).max().unwrap());
     }
     EndState::Falling(new_rock)
 }
 
-fn simulate_motion(input: &str, rock_count: usize) -> u32 {
+fn parse_jets(input: &str) -> Result<Vec<Jet>, ParseError> {
+    let jets = input.trim_end();
+    all_chars(input, jets, "'<' or '>'", |char| matches!(char, '<' | '>'))?;
+    if jets.is_empty() {
+        return Err(ParseError::at(input, jets, "'<' or '>'"));
+    }
+    Ok(jets
+        .chars()
+        .map(|char| match char {
+            '<' => Jet::Left,
+            _ => Jet::Right,
+        })
+        .collect())
+}
+
+/// Drops `rock_count` rocks and returns the height of the tower. `on_step` sees the settled
+/// rocks, the falling rock and the tower's height each time a jet pushes the falling rock.
+fn simulate_motion(
+    jets: &[Jet],
+    rock_count: usize,
+    mut on_step: impl FnMut(&HashSet<(u32, u32)>, &Rock, u32),
+) -> u64 {
     let mut height = 0;
-    let mut jets = input.chars().filter_map(|char| match char {
-        '<' => Some(Jet::Left),
-        '>' => Some(Jet::Right),
-        _ => None,
-    }).cycle();
+    let mut jets = jets.iter().cycle();
     let mut board = HashSet::new();
-    (0..=4).cycle().take(rock_count).for_each(|shape_number| {
+    for (rock_index, shape_number) in (0..=4).cycle().take(rock_count).enumerate() {
         let mut rock = generate_rock(shape_number, height + 4);
         loop {
             rock = jet_step(&board, rock, jets.next().unwrap());
+            on_step(&board, &rock, height);
             match fall_step(&mut board, rock) {
                 EndState::Falling(new_rock) => {
                     rock = new_rock;
-                },
+                }
                 EndState::Landed(new_height) => {
                     height = height.max(new_height);
                     break;
                 }
             }
         }
-        println!("{height}");
-    });
-    height
+        trace!(rock = rock_index + 1, height, "rock landed");
+    }
+    height.into()
 }
 
-pub fn part_1(input: &str) -> String {
-    simulate_motion(input, 2022).to_string()
+impl Solution for Day17 {
+    type Parsed<'a> = Vec<Jet>;
+    type Answer1 = u64;
+    type Answer2 = Answer;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_jets(input)
+    }
+
+    fn part_1(&self, jets: &Vec<Jet>) -> u64 {
+        simulate_motion(jets, 2022, |_, _, _| {})
+    }
+
+    // Dropping a trillion rocks one at a time never finishes; this needs a way to skip over
+    // the stretches where the tower repeats.
+    fn part_2(&self, _jets: &Vec<Jet>) -> Answer {
+        Answer::Unsolved
+    }
 }
 
-pub fn part_2(input: &str) -> String {
-    simulate_motion(input, 1000000000000).to_string()
+/// How many rows at the top of the tower each frame shows.
+#[cfg(feature = "visualize")]
+const WINDOW: u32 = 40;
+
+#[cfg(feature = "visualize")]
+const PALETTE: &[Color] = &[
+    Color::new('.', [16, 16, 24]),
+    Color::new('|', [90, 90, 100]),
+    Color::new('#', [150, 120, 90]),
+    Color::new('@', [230, 110, 60]),
+    Color::new('-', [90, 90, 100]),
+];
+
+/// Draws the top of the chamber each time a jet pushes the falling rock.
+#[cfg(feature = "visualize")]
+impl Visualize for Day17 {
+    fn visualize(&self, jets: &Vec<Jet>, part: Part, recorder: &mut Recorder) {
+        let rock_count = match part {
+            Part::One => 2022,
+            Part::Two => 1000000000000,
+        };
+        simulate_motion(jets, rock_count, |board, rock, height| {
+            recorder.step(|| {
+                // New rocks appear up to seven rows above the tower.
+                let top = (height + 7).max(WINDOW - 1);
+                let mut frame = Frame::new(PALETTE, 9, WINDOW as usize);
+                for row in 0..WINDOW {
+                    let y = top - row;
+                    for x in 0..9 {
+                        let color = if y == 0 {
+                            4
+                        } else if x == 0 || x == 8 {
+                            1
+                        } else if rock.contains(&(x, y)) {
+                            3
+                        } else if board.contains(&(x, y)) {
+                            2
+                        } else {
+                            0
+                        };
+                        frame.set(x as usize, row as usize, color);
+                    }
+                }
+                frame
+            });
+        });
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[rustfmt::skip]
-    const INPUT: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
-
     #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "3068");
+    fn parses_jets() {
+        assert_eq!("<".parse(), Ok(Jet::Left));
+        assert_eq!(">".parse(), Ok(Jet::Right));
     }
 
     #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "1514285714288");
+    fn rejects_anything_but_a_jet() {
+        let error = "^".parse::<Jet>().unwrap_err();
+
+        assert_eq!((error.line(), error.column()), (1, 1));
+        assert_eq!(error.expected(), "'<' or '>'");
     }
 }
+
+common::example_tests!(Day17, example: [part_1, #[ignore = "not solved yet"] part_2]);
@@ -1,29 +1,31 @@
+use common::{Answer, ParseError, Solution};
 
-pub fn part_1(input: &str) -> String {
-    "part1".to_string()
-}
-
-pub fn part_2(input: &str) -> String {
-    "part2".to_string()
-}
+#[derive(Default)]
+pub struct Day20;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl Solution for Day20 {
+    type Parsed<'a> = &'a str;
+    type Answer1 = Answer;
+    type Answer2 = Answer;
 
-    #[rustfmt::skip]
-    const INPUT: &str = concat!(
-        "498,4 -> 498,6 -> 496,6\n",
-        "503,4 -> 502,4 -> 502,9 -> 494,9\n",
-    );
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        Ok(input)
+    }
 
-    #[test]
-    fn part_1_works() {
-        assert_eq!(part_1(INPUT), "part1");
+    fn part_1(&self, _input: &&str) -> Answer {
+        Answer::Unsolved
     }
 
-    #[test]
-    fn part_2_works() {
-        assert_eq!(part_2(INPUT), "part2");
+    fn part_2(&self, _input: &&str) -> Answer {
+        Answer::Unsolved
     }
 }
+
+// Drop a part's `#[ignore]` once it is solved.
+common::example_tests!(
+    Day20,
+    example: [
+        #[ignore = "not solved yet"] part_1,
+        #[ignore = "not solved yet"] part_2,
+    ],
+);
@@ -1,14 +1,5 @@
-use day_20::part_1;
-use day_20::part_2;
-use std::env;
-use std::fs;
+use aoc_2022_day_20::Day20;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let input_path = &args[1];
-    let input = fs::read_to_string(input_path)
-        .unwrap_or_else(|_| panic!("Should have an input file at path: {}", input_path));
-
-    println!("{}", part_1(&input));
-    println!("{}", part_2(&input));
+    common::main::<Day20>();
 }
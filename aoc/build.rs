@@ -0,0 +1,104 @@
+//! Writes `$OUT_DIR/registry.rs`, which registers every `aoc_<year>_day_<dd>` dependency of the
+//! runner, so that a day only has to be added to `Cargo.toml`.
+//!
+//! With the `embed-inputs` feature, also writes `$OUT_DIR/embedded_inputs.rs`: a table that
+//! includes every non-empty `<year>/day_<dd>/assets/input.txt` in the workspace, so the runner
+//! needs no files at run time. Days without an input are listed in a build warning.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn numbered_entries<T: std::str::FromStr>(
+    directory: &Path,
+    prefix: &'static str,
+) -> impl Iterator<Item = (T, PathBuf)> {
+    fs::read_dir(directory)
+        .unwrap_or_else(|error| panic!("{}: {error}", directory.display()))
+        .filter_map(Result::ok)
+        .filter_map(move |entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let number = name.strip_prefix(prefix)?.parse().ok()?;
+            Some((number, entry.path()))
+        })
+}
+
+/// The year and day of each day crate among the dependencies in `manifest`, in order.
+fn day_dependencies(manifest: &str) -> Vec<(u16, u8)> {
+    let mut days: Vec<(u16, u8)> = manifest
+        .lines()
+        .filter_map(|line| {
+            let name = line.split_once(" = ")?.0.strip_prefix("aoc_")?;
+            let (year, day) = name.split_once("_day_")?;
+            Some((year.parse().ok()?, day.parse().ok()?))
+        })
+        .collect();
+    days.sort();
+    days
+}
+
+fn write_registry(manifest_dir: &Path, out_dir: &Path) {
+    let manifest_path = manifest_dir.join("Cargo.toml");
+    println!("cargo::rerun-if-changed={}", manifest_path.display());
+    let manifest = fs::read_to_string(&manifest_path).unwrap();
+
+    let mut registry = String::from("registry! {\n");
+    for (year, day) in day_dependencies(&manifest) {
+        writeln!(
+            registry,
+            "    {year} {day} => aoc_{year}_day_{day:02}::Day{day:02};"
+        )
+        .unwrap();
+    }
+    registry.push_str("}\n");
+    fs::write(out_dir.join("registry.rs"), registry).unwrap();
+}
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    write_registry(&manifest_dir, &out_dir);
+
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_none() {
+        return;
+    }
+    let root = manifest_dir.parent().unwrap();
+    let mut inputs = String::from("pub const INPUTS: &[(u16, u8, &str)] = &[\n");
+    let mut missing: BTreeMap<u16, Vec<u8>> = BTreeMap::new();
+    let mut days: Vec<(u16, u8, PathBuf)> = numbered_entries::<u16>(root, "")
+        .filter(|(_, path)| path.is_dir())
+        .flat_map(|(year, path)| {
+            numbered_entries::<u8>(&path, "day_").map(move |(day, path)| (year, day, path))
+        })
+        .collect();
+    days.sort();
+
+    for (year, day, directory) in days {
+        let assets = directory.join("assets");
+        println!("cargo::rerun-if-changed={}", assets.display());
+        let path = assets.join("input.txt");
+        match fs::metadata(&path) {
+            Ok(metadata) if metadata.len() > 0 => writeln!(
+                inputs,
+                "    ({year}, {day}, include_str!({:?})),",
+                path.display().to_string()
+            )
+            .unwrap(),
+            _ => missing.entry(year).or_default().push(day),
+        }
+    }
+    inputs.push_str("];\n");
+
+    for (year, days) in missing {
+        let label = if days.len() == 1 { "day" } else { "days" };
+        let days: Vec<String> = days.iter().map(u8::to_string).collect();
+        println!(
+            "cargo::warning=no input to embed for {year} {label} {}",
+            days.join(", ")
+        );
+    }
+
+    fs::write(out_dir.join("embedded_inputs.rs"), inputs).unwrap();
+}
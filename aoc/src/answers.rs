@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use common::{Answer, Part};
+
+/// Accepted answers for one year, stored as `<year>/answers.txt`.
+///
+/// Each line holds a day, a part and the answer, separated by single spaces. Newlines and
+/// backslashes inside an answer are escaped as `\n` and `\\` so that multi-line answers such as
+/// day 10's CRT output still fit on one line, and are read back as an [`Answer::Grid`]. Blank
+/// lines and lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct Answers {
+    entries: BTreeMap<(u8, Part), Answer>,
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(io::Error),
+    Syntax { line: usize, message: String },
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswersError::Io(error) => write!(f, "failed to read answers: {error}"),
+            AnswersError::Syntax { line, message } => {
+                write!(f, "invalid answers file at line {line}: {message}")
+            }
+        }
+    }
+}
+
+impl From<io::Error> for AnswersError {
+    fn from(error: io::Error) -> Self {
+        AnswersError::Io(error)
+    }
+}
+
+impl Answers {
+    pub fn path(root: &Path, year: u16) -> PathBuf {
+        root.join(year.to_string()).join("answers.txt")
+    }
+
+    /// Loads the answers at `path`, treating a missing file as an empty registry.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, AnswersError> {
+        let mut answers = Self::default();
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let syntax_error = |message: &str| AnswersError::Syntax {
+                line: index + 1,
+                message: message.to_string(),
+            };
+            let mut fields = line.splitn(3, ' ');
+            let day = fields
+                .next()
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| syntax_error("expected a day number"))?;
+            let part = fields
+                .next()
+                .and_then(|part| part.parse::<u8>().ok())
+                .and_then(Part::from_number)
+                .ok_or_else(|| syntax_error("expected part 1 or 2"))?;
+            let answer = fields
+                .next()
+                .ok_or_else(|| syntax_error("expected an answer"))?;
+            let Ok(answer) = unescape(answer).parse();
+            answers.insert(day, part, answer);
+        }
+        Ok(answers)
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.entries.get(&(day, part))
+    }
+
+    pub fn insert(&mut self, day: u8, part: Part, answer: Answer) {
+        self.entries.insert((day, part), answer);
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut contents = String::from("# day part answer\n");
+        for ((day, part), answer) in self.entries.iter() {
+            contents.push_str(&format!("{day} {part} {}\n", escape(&answer.to_string())));
+        }
+        fs::write(path, contents)
+    }
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut result = String::with_capacity(answer.len());
+    let mut chars = answer.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_multi_line_answers() {
+        let mut answers = Answers::default();
+        answers.insert(10, Part::Two, Answer::grid("#..#\n.##."));
+        answers.insert(1, Part::One, Answer::from(24000));
+
+        let path = std::env::temp_dir().join("aoc_answers_round_trip.txt");
+        answers.save(&path).unwrap();
+        let loaded = Answers::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            loaded.get(10, Part::Two),
+            Some(&Answer::grid("#..#\n.##.\n"))
+        );
+        assert_eq!(loaded.get(1, Part::One), Some(&Answer::Integer(24000)));
+        assert_eq!(loaded.get(1, Part::Two), None);
+    }
+
+    #[test]
+    fn rejects_unknown_parts() {
+        let error = Answers::parse("# day part answer\n1 3 24000\n").unwrap_err();
+        assert!(matches!(error, AnswersError::Syntax { line: 2, .. }));
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::Args;
+use common::alloc::AllocStats;
+use common::{Answer, Part};
+
+use crate::registry::{self, Day};
+use crate::{read_input, workspace_root};
+
+#[derive(Args)]
+pub struct BenchArgs {
+    /// Only benchmark days from this year.
+    year: Option<u16>,
+
+    /// Only benchmark this day.
+    #[arg(requires = "year")]
+    day: Option<u8>,
+
+    /// Number of timed runs per day.
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    iterations: u32,
+
+    /// Baseline file to compare against and save to.
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Overwrite the baseline with this run's medians.
+    #[arg(long)]
+    save_baseline: bool,
+
+    /// Percentage a median may grow past its baseline before it counts as a regression.
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+
+    /// Microseconds a median must also grow by to count as a regression, so that noise in
+    /// phases that only take a few microseconds is not flagged.
+    #[arg(long, default_value_t = 50)]
+    min_delta: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Phase {
+    Parse,
+    Solve(Part),
+}
+
+impl Phase {
+    const ALL: [Phase; 3] = [
+        Phase::Parse,
+        Phase::Solve(Part::One),
+        Phase::Solve(Part::Two),
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|phase| phase.to_string() == name)
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Solve(part) => write!(f, "part{part}"),
+        }
+    }
+}
+
+type Key = (u16, u8, Phase);
+
+#[derive(Debug)]
+struct Stats {
+    min: Duration,
+    median: Duration,
+    max: Duration,
+    /// Heap use of the last run, when the runner counts allocations.
+    alloc: Option<AllocStats>,
+}
+
+impl Stats {
+    /// Summarises at least one sample. The median of an even count is the mean of the middle
+    /// two.
+    fn from_samples(mut samples: Vec<Duration>, alloc: Option<AllocStats>) -> Self {
+        samples.sort();
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+        Self {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+            alloc,
+        }
+    }
+}
+
+/// Median timings from an earlier run, stored one `year day phase nanoseconds` line at a time.
+#[derive(Default)]
+struct Baseline {
+    medians: BTreeMap<Key, Duration>,
+}
+
+impl Baseline {
+    fn default_path() -> PathBuf {
+        workspace_root()
+            .join("target")
+            .join("aoc-bench-baseline.txt")
+    }
+
+    fn load(path: &Path) -> io::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(error),
+        };
+
+        let medians = contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split(' ');
+                let year = fields.next()?.parse().ok()?;
+                let day = fields.next()?.parse().ok()?;
+                let phase = Phase::from_name(fields.next()?)?;
+                let nanos = fields.next()?.parse().ok()?;
+                Some(((year, day, phase), Duration::from_nanos(nanos)))
+            })
+            .collect();
+        Ok(Self { medians })
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = self
+            .medians
+            .iter()
+            .map(|((year, day, phase), median)| {
+                format!("{year} {day} {phase} {}\n", median.as_nanos())
+            })
+            .collect::<String>();
+        fs::write(path, contents)
+    }
+
+    /// How much `median` grew past the baseline's median for `key`, in percent, if it has one.
+    fn change(&self, key: &Key, median: Duration) -> Option<f64> {
+        let previous = self.medians.get(key)?;
+        Some((median.as_secs_f64() / previous.as_secs_f64() - 1.0) * 100.0)
+    }
+
+    /// Whether `median` grew past the baseline's median for `key` by more than `threshold`
+    /// percent and by more than `min_delta`.
+    fn regressed(&self, key: &Key, median: Duration, threshold: f64, min_delta: Duration) -> bool {
+        self.change(key, median)
+            .is_some_and(|change| change > threshold)
+            && self.medians[key].saturating_add(min_delta) < median
+    }
+}
+
+fn sample_day(day: &Day, input: &str, iterations: u32) -> Result<BTreeMap<Phase, Stats>, String> {
+    let solution = (day.solution)();
+    let mut samples: BTreeMap<Phase, Vec<Duration>> = BTreeMap::new();
+    let mut allocs: BTreeMap<Phase, AllocStats> = BTreeMap::new();
+
+    for _ in 0..iterations {
+        let solved = solution
+            .run(input, &Part::ALL)
+            .map_err(|error| error.to_string())?;
+        samples
+            .entry(Phase::Parse)
+            .or_default()
+            .push(solved.parse_time);
+        allocs.extend(solved.parse_alloc.map(|alloc| (Phase::Parse, alloc)));
+        for result in solved.parts {
+            match &result.answer {
+                Answer::Error(error) => return Err(format!("part {}: {error}", result.part)),
+                Answer::Unsolved => continue,
+                _ => {}
+            }
+            samples
+                .entry(Phase::Solve(result.part))
+                .or_default()
+                .push(result.time);
+            allocs.extend(result.alloc.map(|alloc| (Phase::Solve(result.part), alloc)));
+        }
+    }
+
+    Ok(samples
+        .into_iter()
+        .map(|(phase, samples)| {
+            let alloc = allocs.get(&phase).copied();
+            (phase, Stats::from_samples(samples, alloc))
+        })
+        .collect())
+}
+
+/// Times the parse and both solve phases of every selected day that has an input, reporting
+/// min, median and max over `iterations` runs. Medians are compared against the baseline file
+/// when one exists, and the command fails if any phase regressed past both the threshold and
+/// the minimum delta. Parts that are not solved yet are left out, and a part that answers
+/// with an error fails its day, so neither is timed or saved to the baseline.
+pub fn bench(args: BenchArgs) -> ExitCode {
+    let baseline_path = args.baseline.unwrap_or_else(Baseline::default_path);
+    let baseline = match Baseline::load(&baseline_path) {
+        Ok(baseline) => baseline,
+        Err(error) => {
+            eprintln!("{}: {error}", baseline_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let min_delta = Duration::from_micros(args.min_delta);
+    let mut current = Baseline::default();
+    let mut regressions = 0;
+    let mut failed = false;
+
+    print!(
+        "{:<4} {:>3} {:<6} {:>12} {:>12} {:>12} {:>12} {:>8}",
+        "year", "day", "phase", "min", "median", "max", "baseline", "change"
+    );
+    if cfg!(feature = "count-allocations") {
+        print!(" {:>10} {:>12} {:>12}", "allocs", "bytes", "peak");
+    }
+    println!();
+    for day in registry::DAYS.iter().filter(|day| {
+        args.year.is_none_or(|year| day.year == year)
+            && args.day.is_none_or(|number| day.day == number)
+    }) {
+        let input = match read_input(day, None) {
+            Ok(Some(input)) => input,
+            Ok(None) => continue,
+            Err(error) => {
+                eprintln!("{} day {:02}: {error}", day.year, day.day);
+                failed = true;
+                continue;
+            }
+        };
+        let stats = match sample_day(day, &input, args.iterations) {
+            Ok(stats) => stats,
+            Err(error) => {
+                eprintln!("{} day {:02}: {error}", day.year, day.day);
+                failed = true;
+                continue;
+            }
+        };
+
+        for (phase, stats) in stats {
+            let key = (day.year, day.day, phase);
+            let change = baseline.change(&key, stats.median);
+            let regressed = baseline.regressed(&key, stats.median, args.threshold, min_delta);
+            let previous = baseline
+                .medians
+                .get(&key)
+                .map_or_else(|| String::from("-"), |previous| format!("{previous:.2?}"));
+            let change =
+                change.map_or_else(|| String::from("-"), |change| format!("{change:+.1}%"));
+            if regressed {
+                regressions += 1;
+            }
+
+            print!(
+                "{:<4} {:>3} {:<6} {:>12.2?} {:>12.2?} {:>12.2?} {:>12} {:>8}",
+                day.year, day.day, phase, stats.min, stats.median, stats.max, previous, change
+            );
+            if let Some(alloc) = stats.alloc {
+                print!(
+                    " {:>10} {:>12} {:>12}",
+                    alloc.allocations, alloc.bytes, alloc.peak
+                );
+            }
+            println!("{}", if regressed { "  REGRESSED" } else { "" });
+            current.medians.insert(key, stats.median);
+        }
+    }
+
+    if args.save_baseline {
+        let mut merged = baseline;
+        merged.medians.extend(current.medians);
+        if let Err(error) = merged.save(&baseline_path) {
+            eprintln!("{}: {error}", baseline_path.display());
+            return ExitCode::FAILURE;
+        }
+        println!("saved baseline to {}", baseline_path.display());
+    }
+
+    if regressions > 0 {
+        println!(
+            "{regressions} phases regressed by more than {}%",
+            args.threshold
+        );
+    }
+    if failed || regressions > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::registry::tests::DAY;
+
+    fn millis(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn summarises_odd_and_even_sample_counts() {
+        let odd = Stats::from_samples(millis(&[3, 1, 2]), None);
+        assert_eq!(
+            (odd.min, odd.median, odd.max),
+            (
+                Duration::from_millis(1),
+                Duration::from_millis(2),
+                Duration::from_millis(3)
+            )
+        );
+
+        let even = Stats::from_samples(millis(&[4, 1, 3, 2]), None);
+        assert_eq!(
+            (even.min, even.median, even.max),
+            (
+                Duration::from_millis(1),
+                Duration::from_micros(2500),
+                Duration::from_millis(4)
+            )
+        );
+    }
+
+    #[test]
+    fn round_trips_baselines() {
+        let mut baseline = Baseline::default();
+        baseline
+            .medians
+            .insert((2022, 1, Phase::Parse), Duration::from_nanos(1234));
+        baseline.medians.insert(
+            (2022, 17, Phase::Solve(Part::Two)),
+            Duration::from_millis(80),
+        );
+
+        let path = env::temp_dir().join("aoc_bench_baseline_round_trip.txt");
+        baseline.save(&path).unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.medians, baseline.medians);
+        assert!(Baseline::load(&path).unwrap().medians.is_empty());
+    }
+
+    #[test]
+    fn flags_medians_past_the_threshold() {
+        let key = (2022, 1, Phase::Solve(Part::One));
+        let mut baseline = Baseline::default();
+        baseline.medians.insert(key, Duration::from_millis(100));
+        let min_delta = Duration::from_micros(50);
+        let regressed =
+            |median| baseline.regressed(&key, Duration::from_millis(median), 10.0, min_delta);
+
+        assert!(!regressed(90));
+        assert!(!regressed(105));
+        assert!(regressed(120));
+        assert_eq!(
+            baseline.change(&(2022, 2, Phase::Parse), Duration::from_millis(1)),
+            None
+        );
+        assert!(!baseline.regressed(
+            &(2022, 2, Phase::Parse),
+            Duration::from_millis(1),
+            10.0,
+            min_delta
+        ));
+    }
+
+    #[test]
+    fn ignores_noise_in_fast_phases() {
+        let key = (2022, 6, Phase::Parse);
+        let mut baseline = Baseline::default();
+        baseline.medians.insert(key, Duration::from_nanos(400));
+        let min_delta = Duration::from_micros(50);
+
+        assert!(!baseline.regressed(&key, Duration::from_nanos(900), 10.0, min_delta));
+        assert!(baseline.regressed(&key, Duration::from_micros(60), 10.0, min_delta));
+    }
+
+    #[test]
+    fn leaves_out_unsolved_and_failing_parts() {
+        let stats = sample_day(&DAY, "unsolved", 2).unwrap();
+        assert_eq!(
+            stats.into_keys().collect::<Vec<_>>(),
+            [Phase::Parse, Phase::Solve(Part::One)]
+        );
+        assert_eq!(
+            sample_day(&DAY, "failing", 2).unwrap_err(),
+            "part 2: no bytes worth counting"
+        );
+    }
+}
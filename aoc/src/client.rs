@@ -0,0 +1,312 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+use common::Part;
+use ureq::http::Response;
+use ureq::{Agent, Body};
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = concat!("aoc-runner/", env!("CARGO_PKG_VERSION"));
+
+/// Minimum time between two requests to the site, shared by every command and workspace
+/// through the throttle file.
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Connection settings shared by the commands that talk to the Advent of Code site.
+#[derive(Args)]
+pub struct ClientArgs {
+    /// Site to talk to, e.g. a local mock server.
+    #[arg(long, env = "AOC_BASE_URL", default_value = DEFAULT_BASE_URL)]
+    base_url: String,
+
+    /// File holding the session cookie, read when `AOC_SESSION` is not set. Defaults to
+    /// `$XDG_CONFIG_HOME/aoc/session` or `~/.config/aoc/session`.
+    #[arg(long)]
+    session_file: Option<PathBuf>,
+
+    /// Contact details (email or repository URL) added to the user agent, as the site asks
+    /// automated tools to do.
+    #[arg(long, env = "AOC_CONTACT")]
+    contact: Option<String>,
+}
+
+impl ClientArgs {
+    pub fn client(&self) -> Result<Client, ClientError> {
+        let session = match env::var("AOC_SESSION") {
+            Ok(session) => session,
+            Err(_) => {
+                let path = self
+                    .session_file
+                    .clone()
+                    .or_else(default_session_file)
+                    .ok_or(ClientError::NoSession)?;
+                match fs::read_to_string(&path) {
+                    Ok(session) => session,
+                    Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                        return Err(ClientError::NoSession)
+                    }
+                    Err(error) => return Err(ClientError::Io(error)),
+                }
+            }
+        };
+        let session = session.trim();
+        if session.is_empty() {
+            return Err(ClientError::NoSession);
+        }
+
+        let user_agent = match &self.contact {
+            Some(contact) => format!("{USER_AGENT} ({contact})"),
+            None => USER_AGENT.to_string(),
+        };
+        Ok(Client::new(
+            &self.base_url,
+            session,
+            &user_agent,
+            Throttle::new(account_dir().join("last-request"), MIN_REQUEST_INTERVAL),
+        ))
+    }
+}
+
+/// `$XDG_CONFIG_HOME/aoc` or `~/.config/aoc`, where the session cookie is looked for.
+fn config_dir() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config.join("aoc"))
+}
+
+fn default_session_file() -> Option<PathBuf> {
+    Some(config_dir()?.join("session"))
+}
+
+/// Where files about the account rather than the workspace are kept, such as when the site
+/// was last asked something, so that every checkout shares them and `cargo clean` keeps them.
+/// This is the config directory, or a temporary one when there is no home directory.
+pub fn account_dir() -> PathBuf {
+    config_dir().unwrap_or_else(|| env::temp_dir().join("aoc"))
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    NoSession,
+    SessionRejected,
+    NotFound,
+    Status(u16),
+    Http(ureq::Error),
+    Io(io::Error),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::NoSession => write!(
+                f,
+                "no session token; set AOC_SESSION or write it to ~/.config/aoc/session"
+            ),
+            ClientError::SessionRejected => write!(f, "the session token was rejected"),
+            ClientError::NotFound => write!(f, "the puzzle does not exist or is not unlocked yet"),
+            ClientError::Status(status) => write!(f, "unexpected HTTP status {status}"),
+            ClientError::Http(error) => write!(f, "request failed: {error}"),
+            ClientError::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl From<ureq::Error> for ClientError {
+    fn from(error: ureq::Error) -> Self {
+        ClientError::Http(error)
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(error: io::Error) -> Self {
+        ClientError::Io(error)
+    }
+}
+
+/// Spaces requests at least `interval` apart, remembering the last request time in a file so
+/// that back-to-back invocations are limited as well.
+pub struct Throttle {
+    path: PathBuf,
+    interval: Duration,
+}
+
+impl Throttle {
+    pub fn new(path: PathBuf, interval: Duration) -> Self {
+        Self { path, interval }
+    }
+
+    fn wait(&self) -> io::Result<()> {
+        let now = SystemTime::now();
+        let last = fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|millis| millis.trim().parse().ok())
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+        if let Some(elapsed) = last.and_then(|last| now.duration_since(last).ok()) {
+            if let Some(remaining) = self.interval.checked_sub(elapsed) {
+                thread::sleep(remaining);
+            }
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        fs::write(&self.path, millis.to_string())
+    }
+}
+
+/// Blocking client for the Advent of Code site, authenticated with a session cookie.
+pub struct Client {
+    agent: Agent,
+    base_url: String,
+    session: String,
+    throttle: Throttle,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str, user_agent: &str, throttle: Throttle) -> Self {
+        let agent = Agent::config_builder()
+            .user_agent(user_agent)
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+        Self {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            throttle,
+        }
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Downloads the puzzle input for one day.
+    pub fn input(&self, year: u16, day: u8) -> Result<String, ClientError> {
+        self.throttle.wait()?;
+        let response = self
+            .agent
+            .get(format!("{}/{year}/day/{day}/input", self.base_url))
+            .header("Cookie", self.cookie())
+            .call()?;
+        read_body(response)
+    }
+
+    /// Posts an answer for one part and returns the page the site responds with.
+    pub fn submit(
+        &self,
+        year: u16,
+        day: u8,
+        part: Part,
+        answer: &str,
+    ) -> Result<String, ClientError> {
+        self.throttle.wait()?;
+        let response = self
+            .agent
+            .post(format!("{}/{year}/day/{day}/answer", self.base_url))
+            .header("Cookie", self.cookie())
+            .send_form([("level", part.to_string().as_str()), ("answer", answer)])?;
+        read_body(response)
+    }
+}
+
+fn read_body(mut response: Response<Body>) -> Result<String, ClientError> {
+    match response.status().as_u16() {
+        200 => Ok(response.body_mut().read_to_string()?),
+        // The site answers 400 when no session is sent and 500 for a malformed one.
+        400 | 401 | 403 | 500 => Err(ClientError::SessionRejected),
+        404 => Err(ClientError::NotFound),
+        status => Err(ClientError::Status(status)),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    use super::*;
+
+    /// Serves one canned HTTP response on a local port and hands back the raw request it got,
+    /// returning the base URL to point a [`Client`] at.
+    pub fn mock_server(status: &str, body: &str) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            // Bodies are small form posts that arrive with the headers or right after them.
+            stream
+                .set_read_timeout(Some(Duration::from_millis(50)))
+                .unwrap();
+            while let Ok(read) = stream.read(&mut buffer) {
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            sender
+                .send(String::from_utf8_lossy(&request).to_lowercase())
+                .unwrap();
+        });
+        (base_url, receiver)
+    }
+
+    pub fn test_client(base_url: &str, name: &str) -> Client {
+        let throttle = env::temp_dir().join(format!("aoc_throttle_{name}"));
+        Client::new(
+            base_url,
+            "secret",
+            "aoc-runner/test (me@example.com)",
+            Throttle::new(throttle, Duration::ZERO),
+        )
+    }
+
+    #[test]
+    fn downloads_input_with_session_and_user_agent() {
+        let (base_url, request) = mock_server("200 OK", "1000\n2000\n");
+        let client = test_client(&base_url, "download");
+
+        assert_eq!(client.input(2022, 1).unwrap(), "1000\n2000\n");
+        let request = request.recv().unwrap();
+        assert!(request.starts_with("get /2022/day/1/input http/1.1"));
+        assert!(request.contains("cookie: session=secret"));
+        assert!(request.contains("user-agent: aoc-runner/test (me@example.com)"));
+    }
+
+    #[test]
+    fn reports_locked_puzzles() {
+        let (base_url, _request) = mock_server("404 Not Found", "Please don't repeatedly...");
+        let client = test_client(&base_url, "locked");
+
+        assert!(matches!(client.input(2022, 25), Err(ClientError::NotFound)));
+    }
+}
@@ -0,0 +1,280 @@
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::Args;
+use common::{input, Part};
+
+use crate::answers::Answers;
+use crate::registry::{self, Day};
+use crate::report::{cell, time_label, Row, Status};
+use crate::run_all::run_day;
+
+/// Extension of the file next to an input that holds the answers expected for it.
+const ANSWERS_EXTENSION: &str = "answers";
+
+#[derive(Args)]
+pub struct CompareArgs {
+    /// Puzzle year of the day to run.
+    year: u16,
+
+    /// Puzzle day to run.
+    day: u8,
+
+    /// Directory of inputs to run the day on. An input `alice.txt` can have its expected
+    /// answers in `alice.answers`, written like the year's `answers.txt`.
+    inputs: PathBuf,
+
+    /// Only run the given part.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Seconds each input may take before it is reported as timed out and left running in the
+    /// background.
+    #[arg(long, default_value_t = 60)]
+    timeout: u64,
+}
+
+/// The result rows of one input, named after its file.
+struct InputRun {
+    name: String,
+    rows: Vec<Row>,
+    /// Whether an earlier input that timed out was still running alongside this one, which
+    /// makes its timings unreliable.
+    contended: bool,
+}
+
+/// The input files of `directory` in name order, skipping hidden files and answer files.
+fn input_files(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut inputs = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        let answers = path
+            .extension()
+            .is_some_and(|extension| extension == ANSWERS_EXTENSION);
+        if path.is_file() && !hidden && !answers {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Runs `day` on every input in `directory` in turn, checking each part against the input's
+/// answers file when it has one. Inputs that cannot be read or whose answers file is invalid
+/// get an error row instead. An input that times out keeps running in the background, so the
+/// inputs after it are marked as contended.
+fn run_inputs(
+    day: &'static Day,
+    directory: &Path,
+    parts: &[Part],
+    timeout: Duration,
+) -> io::Result<Vec<InputRun>> {
+    let mut runs = Vec::new();
+    let mut contended = false;
+    for path in input_files(directory)? {
+        let name = path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let error_row = |message: String| Row {
+            year: day.year,
+            day: day.day,
+            part: None,
+            answer: None,
+            expected: None,
+            time: None,
+            status: Status::Error(message),
+        };
+        let answers_path = path.with_extension(ANSWERS_EXTENSION);
+        let answers = match Answers::load(&answers_path) {
+            Ok(answers) => answers,
+            Err(error) => {
+                let message = format!("{}: {error}", answers_path.display());
+                runs.push(InputRun {
+                    name,
+                    rows: vec![error_row(message)],
+                    contended,
+                });
+                continue;
+            }
+        };
+        let mut rows = match input::read(&path, false) {
+            Ok(input) => run_day(day, input, parts, timeout),
+            Err(error) => vec![error_row(error.to_string())],
+        };
+        for row in &mut rows {
+            if let Some(part) = row.part {
+                row.expected = answers.get(day.day, part).cloned();
+            }
+        }
+        runs.push(InputRun {
+            name,
+            rows,
+            contended,
+        });
+        contended |= runs
+            .last()
+            .is_some_and(|run| run.rows.iter().any(|row| row.status == Status::TimedOut));
+    }
+    Ok(runs)
+}
+
+/// One line per input and part, followed by what was expected of wrong answers and the
+/// messages of inputs that failed. Times measured while a timed out input was still running
+/// are starred.
+fn table(runs: &[InputRun]) -> String {
+    let rows: Vec<(&InputRun, &Row)> = runs
+        .iter()
+        .flat_map(|run| run.rows.iter().map(move |row| (run, row)))
+        .collect();
+    let answers: Vec<String> = rows
+        .iter()
+        .map(|(_, row)| cell(row.answer.as_ref()))
+        .collect();
+    let name_width = rows
+        .iter()
+        .map(|(run, _)| run.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    let width = answers.iter().map(String::len).max().unwrap_or(0).max(6);
+
+    let mut table = format!(
+        "{:<name_width$} {:>4} {:<width$} {:>12} status\n",
+        "input", "part", "answer", "time"
+    );
+    let mut starred = false;
+    for ((run, row), answer) in rows.iter().zip(&answers) {
+        let part = row
+            .part
+            .map_or_else(|| String::from("-"), |part| part.to_string());
+        let mut time = time_label(row.time);
+        if run.contended && row.time.is_some() {
+            time.push('*');
+            starred = true;
+        }
+        let _ = writeln!(
+            table,
+            "{:<name_width$} {part:>4} {answer:<width$} {time:>12} {}",
+            run.name,
+            row.outcome()
+        );
+    }
+    if starred {
+        table.push_str("\n* timed while an input that timed out was still running\n");
+    }
+    for (run, row) in &rows {
+        let name = &run.name;
+        let part = row
+            .part
+            .map_or_else(String::new, |part| format!(" part {part}"));
+        match (row.outcome(), row.message(), &row.expected) {
+            (_, Some(message), _) => {
+                let _ = write!(table, "\n{name}{part}: {message}\n");
+            }
+            ("fail", _, Some(expected)) => {
+                let _ = write!(table, "\n{name}{part}: expected {}\n", cell(Some(expected)));
+            }
+            _ => {}
+        }
+    }
+    table
+}
+
+/// Runs one day on each input of a directory and prints which inputs pass, fail, panic or time
+/// out. Fails if any input did not finish cleanly or gave a wrong answer.
+pub fn compare(args: CompareArgs) -> ExitCode {
+    let Some(day) = registry::find(args.year, args.day) else {
+        eprintln!("No solution registered for {} day {}.", args.year, args.day);
+        return ExitCode::FAILURE;
+    };
+    let timeout = Duration::from_secs(args.timeout);
+
+    // Panics are reported in the table, so keep the default hook from printing them as well.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let runs = run_inputs(day, &args.inputs, &crate::parts(args.part), timeout);
+    panic::set_hook(hook);
+
+    let runs = match runs {
+        Ok(runs) if runs.is_empty() => {
+            eprintln!("No inputs in {}.", args.inputs.display());
+            return ExitCode::FAILURE;
+        }
+        Ok(runs) => runs,
+        Err(error) => {
+            eprintln!("{}: {error}", args.inputs.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    print!("{}", table(&runs));
+
+    if runs.iter().flat_map(|run| &run.rows).any(Row::failed) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::registry::tests::DAY;
+
+    #[test]
+    fn checks_each_input_against_its_own_answers() {
+        let directory = env::temp_dir().join("aoc_compare_inputs");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("alice.txt"), "a\nb\n").unwrap();
+        fs::write(directory.join("alice.answers"), "1 1 2\n1 2 5\n").unwrap();
+        fs::write(directory.join("bob.txt"), "panic\n").unwrap();
+        fs::write(directory.join("carol.txt"), "c\n").unwrap();
+        fs::write(directory.join("carol.answers"), "1 2\n").unwrap();
+        fs::write(directory.join("dave.txt"), "slow").unwrap();
+        fs::write(directory.join("erin.txt"), "e\n").unwrap();
+        fs::write(directory.join(".notes"), "").unwrap();
+
+        let runs = run_inputs(&DAY, &directory, &Part::ALL, Duration::from_millis(500)).unwrap();
+        let outcomes: Vec<(&str, Option<Part>, &str)> = runs
+            .iter()
+            .flat_map(|run| {
+                let name = run.name.as_str();
+                run.rows
+                    .iter()
+                    .map(move |row| (name, row.part, row.outcome()))
+            })
+            .collect();
+
+        assert_eq!(
+            outcomes,
+            [
+                ("alice.txt", Some(Part::One), "pass"),
+                ("alice.txt", Some(Part::Two), "fail"),
+                ("bob.txt", Some(Part::One), "ok"),
+                ("bob.txt", Some(Part::Two), "panicked"),
+                ("carol.txt", None, "error"),
+                ("dave.txt", Some(Part::One), "ok"),
+                ("dave.txt", Some(Part::Two), "timeout"),
+                ("erin.txt", Some(Part::One), "ok"),
+                ("erin.txt", Some(Part::Two), "ok"),
+            ]
+        );
+        let contended: Vec<bool> = runs.iter().map(|run| run.contended).collect();
+        assert_eq!(contended, [false, false, false, false, true]);
+        let table = table(&runs);
+        assert!(table.contains("\nalice.txt part 2: expected 5\n"));
+        assert!(table.contains("\nbob.txt part 2: part 2 is not done\n"));
+        assert!(table.contains("\n* timed while an input that timed out was still running\n"));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
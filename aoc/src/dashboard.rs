@@ -0,0 +1,856 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use clap::Args;
+use common::examples::{self, Example};
+use common::{input, Answer, Part, PartResult};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::answers::Answers;
+use crate::registry::{self, Day};
+use crate::run_all::panic_message;
+use crate::{cargo, read_input, workspace_root};
+
+/// Name of the threads that check and run days, whose panics the dashboard shows itself.
+const WORKER: &str = "dashboard-worker";
+
+#[derive(Args)]
+pub struct DashboardArgs {
+    /// Only list days from this year.
+    year: Option<u16>,
+
+    /// Seconds a day may take on its real input before it is reported as timed out and left
+    /// running in the background.
+    #[arg(long, default_value_t = 60)]
+    timeout: u64,
+
+    /// Start with this day selected, as `<year>-<day>`. Set when restarting after a rebuild.
+    #[arg(long, hide = true, value_parser = parse_selection)]
+    select: Option<(u16, u8)>,
+}
+
+fn parse_selection(value: &str) -> Result<(u16, u8), String> {
+    value
+        .split_once('-')
+        .and_then(|(year, day)| Some((year.parse().ok()?, day.parse().ok()?)))
+        .ok_or_else(|| format!("expected <year>-<day>, got {value:?}"))
+}
+
+/// One example answer from `assets/examples.toml` and what the solution gave instead.
+struct ExampleCheck {
+    file: String,
+    part: Part,
+    expected: Answer,
+    got: Result<Answer, String>,
+}
+
+impl ExampleCheck {
+    fn passed(&self) -> bool {
+        self.got.as_ref() == Ok(&self.expected)
+    }
+
+    /// Whether the part has no answer to check yet, which does not fail the day's tests.
+    fn unsolved(&self) -> bool {
+        self.got.as_ref().is_ok_and(Answer::is_unsolved)
+    }
+}
+
+/// Everything the dashboard learns about a day by running it in the background.
+struct Checked {
+    /// The example checks, or why the manifest could not be read.
+    examples: Result<Vec<ExampleCheck>, String>,
+    has_input: bool,
+    /// Whether no part of the day is solved yet, as when it was scaffolded.
+    stub: bool,
+}
+
+enum PartRun {
+    Running,
+    Solved(PartResult),
+    Failed(String),
+}
+
+/// A day's place in the list, from its first check on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Status {
+    Running,
+    /// Every part matches the year's `answers.txt`.
+    Solved,
+    /// Every part gives an answer, but `answers.txt` lacks some of them.
+    Unverified,
+    /// Some parts are not solved yet.
+    Unsolved,
+    Stub,
+    NoInput,
+    FailingTests,
+    WrongAnswer,
+    /// The real input failed to parse, or the solution panicked or timed out on it.
+    Error,
+}
+
+impl Status {
+    fn label(&self) -> &'static str {
+        match self {
+            Status::Running => "running",
+            Status::Solved => "solved",
+            Status::Unverified => "unverified",
+            Status::Unsolved => "unsolved",
+            Status::Stub => "stub",
+            Status::NoInput => "no input",
+            Status::FailingTests => "failing tests",
+            Status::WrongAnswer => "wrong answer",
+            Status::Error => "error",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Status::Running | Status::NoInput => Color::DarkGray,
+            Status::Solved => Color::Green,
+            Status::Unverified => Color::Cyan,
+            Status::Unsolved | Status::Stub => Color::Yellow,
+            Status::FailingTests | Status::WrongAnswer | Status::Error => Color::Red,
+        }
+    }
+}
+
+struct Entry {
+    day: &'static Day,
+    checked: Option<Checked>,
+    runs: BTreeMap<Part, PartRun>,
+}
+
+impl Entry {
+    fn status(&self, answers: Option<&Answers>) -> Status {
+        let Some(checked) = &self.checked else {
+            return Status::Running;
+        };
+        if checked.stub {
+            return Status::Stub;
+        }
+        match &checked.examples {
+            Ok(checks)
+                if checks
+                    .iter()
+                    .all(|check| check.passed() || check.unsolved()) => {}
+            _ => return Status::FailingTests,
+        }
+        if !checked.has_input {
+            return Status::NoInput;
+        }
+
+        let mut status = Status::Solved;
+        for (&part, run) in &self.runs {
+            let expected = answers.and_then(|answers| answers.get(self.day.day, part));
+            match (run, expected) {
+                (PartRun::Running, _) => return Status::Running,
+                (PartRun::Failed(_), _) => return Status::Error,
+                (PartRun::Solved(result), Some(expected)) if result.answer != *expected => {
+                    status = Status::WrongAnswer;
+                }
+                (PartRun::Solved(result), None) if result.answer.is_unsolved() => {
+                    if matches!(status, Status::Solved | Status::Unverified) {
+                        status = Status::Unsolved;
+                    }
+                }
+                (PartRun::Solved(_), None) if status == Status::Solved => {
+                    status = Status::Unverified;
+                }
+                (PartRun::Solved(_), _) => {}
+            }
+        }
+        status
+    }
+}
+
+enum Message {
+    Checked(usize, Checked),
+    Ran(usize, Result<Vec<PartResult>, String>),
+}
+
+/// Solves `parts` of `input` with a fresh solution after setting `params`, turning parse
+/// errors and panics into messages.
+fn solve(
+    day: &Day,
+    input: &str,
+    params: &[(String, String)],
+    parts: &[Part],
+) -> Result<Vec<PartResult>, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut solution = (day.solution)();
+        for (name, value) in params {
+            solution
+                .set_param(name, value)
+                .map_err(|error| error.to_string())?;
+        }
+        let solved = solution
+            .run(input, parts)
+            .map_err(|error| error.to_string())?;
+        Ok(solved.parts)
+    }))
+    .unwrap_or_else(|payload| Err(format!("panicked: {}", panic_message(&*payload))))
+}
+
+/// Solves `parts` of `input` like [`solve`], but on a worker of its own, giving up on it after
+/// `timeout`. Rust cannot stop a thread, so an abandoned day keeps running in the background.
+fn solve_within(
+    day: &'static Day,
+    input: String,
+    params: Vec<(String, String)>,
+    parts: &[Part],
+    timeout: Duration,
+) -> Result<Vec<PartResult>, String> {
+    let (sender, receiver) = mpsc::channel();
+    let parts = parts.to_vec();
+    spawn_worker(move || {
+        let _ = sender.send(solve(day, &input, &params, &parts));
+    });
+    receiver
+        .recv_timeout(timeout)
+        .unwrap_or_else(|_| Err(format!("timed out after {timeout:?}")))
+}
+
+/// Solves every part listed in the day's `assets/examples.toml` with [`solve_within`], so that
+/// an example the day cannot finish times out instead of stalling the check.
+fn check_examples(day: &'static Day, timeout: Duration) -> Result<Vec<ExampleCheck>, String> {
+    let assets = day.directory(workspace_root()).join("assets");
+    let mut checks = Vec::new();
+    for Example {
+        file,
+        params,
+        expected,
+    } in examples::read_manifest(&assets)?
+    {
+        let path = assets.join(&file);
+        let raw =
+            fs::read_to_string(&path).map_err(|error| format!("{}: {error}", path.display()))?;
+        let (text, _) = input::normalize(&raw);
+        for (part, expected) in expected {
+            let got = solve_within(day, text.to_string(), params.clone(), &[part], timeout)
+                .map(|mut results| results.remove(0).answer);
+            let Ok(expected) = expected.parse();
+            checks.push(ExampleCheck {
+                file: file.clone(),
+                part,
+                expected,
+                got,
+            });
+        }
+    }
+    Ok(checks)
+}
+
+/// Checks the examples and solves the real input within `timeout`, which is also how stubs
+/// give themselves away. Days without an input are probed with an empty one instead, and an
+/// input that cannot be read counts as a failed run.
+fn check_day(index: usize, day: &'static Day, timeout: Duration, messages: &Sender<Message>) {
+    let examples = check_examples(day, timeout);
+    let (has_input, probe) = match read_input(day, None) {
+        Ok(input) => (
+            input.is_some(),
+            solve_within(
+                day,
+                input.unwrap_or_default(),
+                Vec::new(),
+                &Part::ALL,
+                timeout,
+            ),
+        ),
+        Err(error) => (true, Err(error.to_string())),
+    };
+    let stub = probe
+        .as_ref()
+        .is_ok_and(|results| results.iter().all(|result| result.answer.is_unsolved()));
+    // The answers go first, so that the day is never shown as checked without them.
+    if has_input {
+        let _ = messages.send(Message::Ran(index, probe));
+    }
+    let checked = Checked {
+        examples,
+        has_input,
+        stub,
+    };
+    let _ = messages.send(Message::Checked(index, checked));
+}
+
+fn spawn_worker(work: impl FnOnce() + Send + 'static) {
+    thread::Builder::new()
+        .name(WORKER.into())
+        .spawn(work)
+        .expect("Should be able to spawn a worker thread.");
+}
+
+enum Action {
+    Continue,
+    Quit,
+    Rebuild,
+}
+
+struct App {
+    entries: Vec<Entry>,
+    answers: BTreeMap<u16, Answers>,
+    list: ListState,
+    /// Whether the example and real input are shown instead of the results.
+    inputs: bool,
+    example: usize,
+    scroll: u16,
+    /// How long a day may take on its real input.
+    timeout: Duration,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+}
+
+impl App {
+    fn new(days: Vec<&'static Day>, select: Option<(u16, u8)>, timeout: Duration) -> Self {
+        let selected = select
+            .and_then(|(year, day)| {
+                days.iter()
+                    .position(|entry| entry.year == year && entry.day == day)
+            })
+            .unwrap_or(0);
+        let (sender, receiver) = mpsc::channel();
+        let mut app = App {
+            entries: days
+                .into_iter()
+                .map(|day| Entry {
+                    day,
+                    checked: None,
+                    runs: BTreeMap::new(),
+                })
+                .collect(),
+            answers: BTreeMap::new(),
+            list: ListState::default().with_selected(Some(selected)),
+            inputs: false,
+            example: 0,
+            scroll: 0,
+            timeout,
+            sender,
+            receiver,
+        };
+        app.load_answers();
+        app.check_all();
+        app
+    }
+
+    fn selected(&self) -> usize {
+        self.list.selected().unwrap_or(0)
+    }
+
+    /// Reads every listed year's `answers.txt`, treating a broken one as empty.
+    fn load_answers(&mut self) {
+        for entry in &self.entries {
+            let year = entry.day.year;
+            let answers = Answers::load(&Answers::path(workspace_root(), year)).unwrap_or_default();
+            self.answers.insert(year, answers);
+        }
+    }
+
+    /// Checks every day on as many workers as there are CPUs, in list order.
+    fn check_all(&mut self) {
+        let queue: Vec<(usize, &'static Day)> = self
+            .entries
+            .iter()
+            .map(|entry| entry.day)
+            .enumerate()
+            .collect();
+        let queue = Arc::new(Mutex::new(queue.into_iter()));
+        let jobs = thread::available_parallelism().map_or(1, |jobs| jobs.get());
+        for _ in 0..jobs {
+            let queue = Arc::clone(&queue);
+            let (timeout, sender) = (self.timeout, self.sender.clone());
+            spawn_worker(move || {
+                while let Some((index, day)) = queue.lock().unwrap().next() {
+                    check_day(index, day, timeout, &sender);
+                }
+            });
+        }
+    }
+
+    fn recheck(&mut self, index: usize) {
+        self.load_answers();
+        let entry = &mut self.entries[index];
+        entry.checked = None;
+        entry.runs.clear();
+        let (day, timeout, sender) = (entry.day, self.timeout, self.sender.clone());
+        spawn_worker(move || check_day(index, day, timeout, &sender));
+    }
+
+    fn run(&mut self, index: usize, parts: &[Part]) {
+        let entry = &mut self.entries[index];
+        let input = match read_input(entry.day, None) {
+            Ok(Some(input)) => input,
+            Ok(None) => return,
+            Err(error) => {
+                for &part in parts {
+                    entry.runs.insert(part, PartRun::Failed(error.to_string()));
+                }
+                return;
+            }
+        };
+        for &part in parts {
+            entry.runs.insert(part, PartRun::Running);
+        }
+        let (day, parts) = (entry.day, parts.to_vec());
+        let (timeout, sender) = (self.timeout, self.sender.clone());
+        spawn_worker(move || {
+            let results = solve_within(day, input, Vec::new(), &parts, timeout);
+            let _ = sender.send(Message::Ran(index, results));
+        });
+    }
+
+    fn receive(&mut self) {
+        while let Ok(message) = self.receiver.try_recv() {
+            match message {
+                Message::Checked(index, checked) => self.entries[index].checked = Some(checked),
+                Message::Ran(index, Ok(results)) => {
+                    for result in results {
+                        let part = result.part;
+                        let run = match result.answer.error() {
+                            Some(error) => PartRun::Failed(error.to_string()),
+                            None => PartRun::Solved(result),
+                        };
+                        self.entries[index].runs.insert(part, run);
+                    }
+                }
+                Message::Ran(index, Err(error)) => {
+                    for run in self.entries[index].runs.values_mut() {
+                        if matches!(run, PartRun::Running) {
+                            *run = PartRun::Failed(error.clone());
+                        }
+                    }
+                    // A run that fails before reporting a part has nothing to replace.
+                    let runs = &mut self.entries[index].runs;
+                    if runs.is_empty() {
+                        runs.insert(Part::One, PartRun::Failed(error));
+                    }
+                }
+            }
+        }
+    }
+
+    fn key(&mut self, code: KeyCode) -> Action {
+        let selected = self.selected();
+        match code {
+            KeyCode::Char('q') => return Action::Quit,
+            KeyCode::Esc if self.inputs => self.inputs = false,
+            KeyCode::Esc => return Action::Quit,
+            KeyCode::Up | KeyCode::Char('k') => self.select(selected.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.select(selected + 1),
+            KeyCode::Char('1') => self.run(selected, &[Part::One]),
+            KeyCode::Char('2') => self.run(selected, &[Part::Two]),
+            KeyCode::Enter | KeyCode::Char('a') => self.run(selected, &Part::ALL),
+            KeyCode::Char('r') => self.recheck(selected),
+            KeyCode::Char('b') => return Action::Rebuild,
+            KeyCode::Char('i') => {
+                self.inputs = !self.inputs;
+                self.scroll = 0;
+            }
+            KeyCode::Char('e') => {
+                self.example += 1;
+                self.scroll = 0;
+            }
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn select(&mut self, index: usize) {
+        if index < self.entries.len() && index != self.selected() {
+            self.list.select(Some(index));
+            self.example = 0;
+            self.scroll = 0;
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [list, details] =
+            Layout::horizontal([Constraint::Length(26), Constraint::Min(0)]).areas(main);
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let status = entry.status(self.answers.get(&entry.day.year));
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{} {:02}  ", entry.day.year, entry.day.day)),
+                    Span::styled(status.label(), Style::new().fg(status.color())),
+                ]))
+            })
+            .collect();
+        let items = List::new(items)
+            .block(Block::bordered().title(" Days "))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(items, list, &mut self.list);
+
+        let entry = &self.entries[self.selected()];
+        if self.inputs {
+            self.draw_inputs(frame, details);
+        } else {
+            let title = format!(" {} day {:02} ", entry.day.year, entry.day.day);
+            let details_text = self.details(entry);
+            frame.render_widget(
+                Paragraph::new(details_text)
+                    .block(Block::bordered().title(title))
+                    .scroll((self.scroll, 0)),
+                details,
+            );
+        }
+
+        let keys = if self.inputs {
+            "e next example  PgUp/PgDn scroll  i/Esc results  q quit"
+        } else {
+            "↑↓ select  1/2 run part  a run both  r recheck  b rebuild  i inputs  q quit"
+        };
+        frame.render_widget(Paragraph::new(keys).style(Color::DarkGray), help);
+    }
+
+    fn details(&self, entry: &Entry) -> Vec<Line<'static>> {
+        let answers = self.answers.get(&entry.day.year);
+        let status = entry.status(answers);
+        let mut lines = vec![
+            Line::from(vec![
+                Span::raw("Status: "),
+                Span::styled(status.label(), Style::new().fg(status.color())),
+            ]),
+            Line::raw(""),
+            Line::styled("Examples", Modifier::BOLD),
+        ];
+        match entry.checked.as_ref().map(|checked| &checked.examples) {
+            None => lines.push(Line::raw("  checking...")),
+            Some(Err(error)) => lines.push(Line::styled(format!("  {error}"), Color::Red)),
+            Some(Ok(checks)) if checks.is_empty() => lines.push(Line::raw("  none listed")),
+            Some(Ok(checks)) => {
+                for check in checks {
+                    let label = format!("  {} part {}: ", check.file, check.part);
+                    let outcome = match &check.got {
+                        Ok(_) if check.passed() => Span::styled("pass", Color::Green),
+                        Ok(_) if check.unsolved() => Span::styled("not solved yet", Color::Yellow),
+                        Ok(got) => Span::styled(
+                            format!("got {}, expected {}", one_line(got), check.expected),
+                            Color::Red,
+                        ),
+                        Err(error) => Span::styled(error.clone(), Color::Red),
+                    };
+                    lines.push(Line::from(vec![Span::raw(label), outcome]));
+                }
+            }
+        }
+
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("Input", Modifier::BOLD));
+        if entry
+            .checked
+            .as_ref()
+            .is_some_and(|checked| !checked.has_input)
+        {
+            let path = entry.day.input_path(workspace_root());
+            lines.push(Line::raw(format!("  no input at {}", path.display())));
+        }
+        for (&part, run) in &entry.runs {
+            let label = Span::raw(format!("  part {part}: "));
+            let result = match run {
+                PartRun::Running => vec![Span::raw("running...")],
+                PartRun::Failed(error) => vec![Span::styled(error.clone(), Color::Red)],
+                PartRun::Solved(result) => {
+                    let expected = answers.and_then(|answers| answers.get(entry.day.day, part));
+                    let verdict = match expected {
+                        Some(expected) if *expected == result.answer => {
+                            Span::styled("matches answers.txt", Color::Green)
+                        }
+                        Some(expected) => {
+                            Span::styled(format!("expected {}", one_line(expected)), Color::Red)
+                        }
+                        None if result.answer.is_unsolved() => {
+                            Span::styled("not solved yet", Color::Yellow)
+                        }
+                        None => Span::styled("not in answers.txt", Color::Cyan),
+                    };
+                    vec![
+                        Span::raw(format!("{}  ", one_line(&result.answer))),
+                        Span::styled(format!("{:.2?}  ", result.time), Color::DarkGray),
+                        verdict,
+                    ]
+                }
+            };
+            lines.push(Line::from([vec![label], result].concat()));
+            if let PartRun::Solved(PartResult { answer, .. }) = run {
+                if answer.is_multi_line() {
+                    let answer = answer.to_string();
+                    lines.extend(answer.lines().map(|row| Line::raw(format!("    {row}"))));
+                }
+            }
+        }
+        lines
+    }
+
+    /// Shows one of the day's examples next to its real input.
+    fn draw_inputs(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let entry = &self.entries[self.selected()];
+        let assets = entry.day.directory(workspace_root()).join("assets");
+        let mut files: Vec<String> = examples::read_manifest(&assets)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|example| example.file)
+            .collect();
+        files.dedup();
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(area);
+
+        let (title, example) = match files.get(self.example % files.len().max(1)) {
+            Some(file) => (
+                format!(" {file} "),
+                fs::read_to_string(assets.join(file)).unwrap_or_default(),
+            ),
+            None => (String::from(" no examples "), String::new()),
+        };
+        let real = read_input(entry.day, None)
+            .map_or_else(|error| error.to_string(), Option::unwrap_or_default);
+        for (area, title, text) in [(left, title, example), (right, " input.txt ".into(), real)] {
+            frame.render_widget(
+                Paragraph::new(text)
+                    .block(Block::bordered().title(title))
+                    .scroll((self.scroll, 0)),
+                area,
+            );
+        }
+    }
+}
+
+/// Pictures do not fit on one line, so they are summarised here and drawn below.
+fn one_line(answer: &Answer) -> String {
+    match answer {
+        Answer::Grid(rows) => format!("<{} rows>", rows.len()),
+        answer => answer.to_string(),
+    }
+}
+
+/// Rebuilds the runner with the features it was built with, showing cargo's output.
+fn rebuild() -> io::Result<bool> {
+    let mut command = cargo();
+    command.args(["build", "-p", "aoc"]);
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    let features: Vec<&str> = [
+        ("count-allocations", cfg!(feature = "count-allocations")),
+        ("embed-inputs", cfg!(feature = "embed-inputs")),
+        ("visualize", cfg!(feature = "visualize")),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect();
+    if !features.is_empty() {
+        command.args(["--features", &features.join(",")]);
+    }
+    Ok(command.status()?.success())
+}
+
+/// Starts the freshly built runner in place of this one, with the same arguments and
+/// selection.
+fn restart(executable: PathBuf, args: &DashboardArgs, selected: &Day) -> io::Result<ExitCode> {
+    let mut command = Command::new(executable);
+    command.arg("dashboard");
+    if let Some(year) = args.year {
+        command.arg(year.to_string());
+    }
+    command.arg(format!("--timeout={}", args.timeout));
+    command.arg(format!("--select={}-{}", selected.year, selected.day));
+    #[cfg(unix)]
+    return Err(command.exec());
+    #[cfg(not(unix))]
+    return Ok(ExitCode::from(command.status()?.code().unwrap_or(1) as u8));
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<Action> {
+    loop {
+        app.receive();
+        terminal.draw(|frame| app.draw(frame))?;
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                match app.key(key.code) {
+                    Action::Continue => {}
+                    action => return Ok(action),
+                }
+            }
+        }
+    }
+}
+
+/// Lists every registered day with its status and lets the user run parts, compare inputs and
+/// rebuild the runner after editing a solution, all without leaving the terminal.
+pub fn dashboard(args: DashboardArgs) -> ExitCode {
+    let days: Vec<&'static Day> = registry::DAYS
+        .iter()
+        .filter(|day| args.year.is_none_or(|year| day.year == year))
+        .collect();
+    if days.is_empty() {
+        eprintln!("No days registered for {}.", args.year.unwrap_or_default());
+        return ExitCode::FAILURE;
+    }
+    // The path has to be read before a rebuild replaces the file behind it.
+    let executable = env::current_exe();
+
+    let mut terminal = ratatui::init();
+    // Panics in solutions are shown in the dashboard, so only pass on the runner's own, which
+    // the hook installed above handles by restoring the terminal first.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if thread::current().name() != Some(WORKER) {
+            hook(info);
+        }
+    }));
+
+    let mut app = App::new(days, args.select, Duration::from_secs(args.timeout));
+    let outcome = loop {
+        let action = event_loop(&mut terminal, &mut app);
+        ratatui::restore();
+        match action {
+            Ok(Action::Rebuild) => match rebuild() {
+                Ok(true) => {
+                    let selected = app.entries[app.selected()].day;
+                    let restarted =
+                        executable.and_then(|executable| restart(executable, &args, selected));
+                    break restarted.map_err(|error| format!("failed to restart: {error}"));
+                }
+                Ok(false) => {
+                    eprintln!("Build failed, press Enter to return to the dashboard.");
+                    let _ = io::stdin().read_line(&mut String::new());
+                    terminal = ratatui::init();
+                }
+                Err(error) => break Err(format!("failed to run cargo: {error}")),
+            },
+            Ok(_) => break Ok(ExitCode::SUCCESS),
+            Err(error) => break Err(error.to_string()),
+        }
+    };
+    outcome.unwrap_or_else(|error| {
+        eprintln!("{error}");
+        ExitCode::FAILURE
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::tests::DAY;
+
+    fn entry(checked: Checked, answers: &[(Part, &str)]) -> Entry {
+        let runs = answers.iter().map(|&(part, answer)| {
+            let result = PartResult {
+                part,
+                answer: answer.into(),
+                time: Duration::ZERO,
+                alloc: None,
+            };
+            (part, PartRun::Solved(result))
+        });
+        Entry {
+            day: &DAY,
+            checked: Some(checked),
+            runs: runs.collect(),
+        }
+    }
+
+    fn example(expected: &str, got: &str) -> ExampleCheck {
+        ExampleCheck {
+            file: "example.txt".into(),
+            part: Part::One,
+            expected: expected.into(),
+            got: Ok(got.into()),
+        }
+    }
+
+    #[test]
+    fn solve_reports_parse_errors_and_panics() {
+        let answers = solve(&DAY, "fine", &[], &Part::ALL).unwrap();
+        assert_eq!(answers[1].answer, Answer::from(4_usize));
+        assert!(solve(&DAY, "bad", &[], &Part::ALL)
+            .unwrap_err()
+            .contains("expected a mode"));
+
+        assert_eq!(
+            solve(&DAY, "panic", &[], &Part::ALL).unwrap_err(),
+            "panicked: part 2 is not done"
+        );
+    }
+
+    #[test]
+    fn gives_up_on_slow_inputs() {
+        let timeout = Duration::from_millis(200);
+        assert!(solve_within(&DAY, "fine".into(), Vec::new(), &Part::ALL, timeout).is_ok());
+        assert_eq!(
+            solve_within(&DAY, "slow".into(), Vec::new(), &Part::ALL, timeout).unwrap_err(),
+            "timed out after 200ms"
+        );
+    }
+
+    #[test]
+    fn orders_statuses_from_stubs_to_answers() {
+        let checked = |examples, has_input, stub| Checked {
+            examples: Ok(examples),
+            has_input,
+            stub,
+        };
+        let answers = Answers::parse("1 1 24\n1 2 93\n").unwrap();
+        let status = |entry: Entry| entry.status(Some(&answers));
+
+        assert_eq!(
+            status(entry(checked(vec![], true, true), &[])),
+            Status::Stub
+        );
+        assert_eq!(
+            status(entry(checked(vec![example("24", "25")], true, false), &[])),
+            Status::FailingTests
+        );
+        assert_eq!(
+            status(entry(checked(vec![example("24", "24")], false, false), &[])),
+            Status::NoInput
+        );
+        let solved = [(Part::One, "24"), (Part::Two, "93")];
+        assert_eq!(
+            status(entry(checked(vec![], true, false), &solved)),
+            Status::Solved
+        );
+        let wrong = [(Part::One, "24"), (Part::Two, "94")];
+        assert_eq!(
+            status(entry(checked(vec![], true, false), &wrong)),
+            Status::WrongAnswer
+        );
+        let mut unsolved = entry(checked(vec![], true, false), &solved[..1]);
+        let result = PartResult {
+            part: Part::Two,
+            answer: Answer::Unsolved,
+            time: Duration::ZERO,
+            alloc: None,
+        };
+        unsolved.runs.insert(Part::Two, PartRun::Solved(result));
+        assert_eq!(unsolved.status(None), Status::Unsolved);
+        let solved = entry(checked(vec![], true, false), &solved);
+        assert_eq!(solved.status(None), Status::Unverified);
+        assert_eq!(parse_selection("2022-14"), Ok((2022, 14)));
+    }
+}
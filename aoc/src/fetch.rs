@@ -0,0 +1,123 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+
+use clap::Args;
+
+use crate::client::{Client, ClientArgs, ClientError};
+use crate::registry::{self, Day};
+use crate::{read_input, workspace_root};
+
+#[derive(Args)]
+pub struct FetchArgs {
+    /// Puzzle year to download inputs for.
+    year: u16,
+
+    /// Only download this day's input instead of every registered day of the year.
+    day: Option<u8>,
+
+    #[command(flatten)]
+    client: ClientArgs,
+}
+
+enum Fetched {
+    Cached,
+    Downloaded,
+}
+
+/// Writes the day's input to `path` unless a non-empty copy is already there, so the site is
+/// only asked once per input. The client is only connected once something actually needs
+/// downloading, which lets a fully cached year work without a session token.
+fn fetch_input(
+    client: &mut Option<Client>,
+    connect: impl FnOnce() -> Result<Client, ClientError>,
+    day: &Day,
+    path: &Path,
+) -> Result<Fetched, ClientError> {
+    let cached = read_input(day, Some(path)).map_err(io::Error::other)?;
+    if cached.is_some() {
+        return Ok(Fetched::Cached);
+    }
+    let client = match client {
+        Some(client) => client,
+        None => client.insert(connect()?),
+    };
+    let input = client.input(day.year, day.day)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, input)?;
+    Ok(Fetched::Downloaded)
+}
+
+/// Downloads missing inputs into each day's `assets/input.txt`.
+pub fn fetch(args: FetchArgs) -> ExitCode {
+    let days = match args.day {
+        Some(number) => match registry::find(args.year, number) {
+            Some(day) => vec![day],
+            None => {
+                eprintln!(
+                    "No solution registered for {} day {number}; create it with `aoc new {} {number}`.",
+                    args.year, args.year
+                );
+                return ExitCode::FAILURE;
+            }
+        },
+        None => registry::for_year(args.year).collect(),
+    };
+
+    let mut client = None;
+    let mut failed = false;
+    for day in days {
+        let path = day.input_path(workspace_root());
+        match fetch_input(&mut client, || args.client.client(), day, &path) {
+            Ok(Fetched::Cached) => println!("{} day {:02}: cached", day.year, day.day),
+            Ok(Fetched::Downloaded) => println!("{} day {:02}: downloaded", day.year, day.day),
+            Err(error) => {
+                eprintln!("{} day {:02}: {error}", day.year, day.day);
+                failed = true;
+                if matches!(error, ClientError::NoSession | ClientError::SessionRejected) {
+                    break;
+                }
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::client::tests::{mock_server, test_client};
+
+    #[test]
+    fn downloads_once_and_then_uses_the_cache() {
+        let path = env::temp_dir().join("aoc_fetch_cache").join("input.txt");
+        let _ = fs::remove_file(&path);
+        let day = registry::find(2022, 1).unwrap();
+        let (base_url, _request) = mock_server("200 OK", "1000\n");
+        let mut client = None;
+
+        let fetched = fetch_input(
+            &mut client,
+            || Ok(test_client(&base_url, "fetch")),
+            day,
+            &path,
+        );
+        assert!(matches!(fetched, Ok(Fetched::Downloaded)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1000\n");
+
+        // The mock server only answers once, so a second download would fail.
+        let fetched = fetch_input(&mut client, || unreachable!(), day, &path);
+        assert!(matches!(fetched, Ok(Fetched::Cached)));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,274 @@
+mod answers;
+mod bench;
+mod client;
+mod compare;
+mod dashboard;
+mod fetch;
+mod registry;
+mod report;
+mod run_all;
+mod scaffold;
+mod submit;
+mod verify;
+#[cfg(feature = "visualize")]
+mod visualize;
+mod watch;
+
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, ExitCode};
+
+use clap::{ArgAction, Args, Parser, Subcommand};
+use common::input::{self, InputError};
+use common::Part;
+
+use registry::Day;
+
+/// Runs Advent of Code solutions from every year and day crate in the workspace.
+#[derive(Parser)]
+#[command(
+    name = "aoc",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    run: RunArgs,
+
+    /// Report what the solutions are doing on stderr, more with each repetition. Without it,
+    /// `RUST_LOG` sets what is reported.
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Time the parse and solve phases of each day over several runs.
+    ///
+    /// Build with `--release` for meaningful numbers.
+    Bench(bench::BenchArgs),
+
+    /// Run one day on every input in a directory, such as the inputs of several people.
+    ///
+    /// Checks each input against the answers file next to it, if there is one, and reports
+    /// which inputs pass, fail, panic or time out.
+    Compare(compare::CompareArgs),
+
+    /// Browse every day in an interactive terminal view.
+    ///
+    /// Shows whether each day is solved, still a stub, missing its input or failing its example
+    /// tests, and runs parts, shows the inputs and rebuilds the runner on request.
+    Dashboard(dashboard::DashboardArgs),
+
+    /// Download missing puzzle inputs into each day's `assets/input.txt`.
+    ///
+    /// Reads the session token from `AOC_SESSION` or the session file.
+    Fetch(fetch::FetchArgs),
+
+    /// Create a new day crate from the templates and register it with the runner.
+    New(scaffold::NewArgs),
+
+    /// Send an answer to the site and record the verdict.
+    ///
+    /// Refuses answers that an earlier response already ruled out, and adds correct ones to
+    /// the year's `answers.txt`.
+    Submit(submit::SubmitArgs),
+
+    /// Check every day that has an input against the year's `answers.txt`.
+    Verify {
+        /// Only verify days from this year.
+        year: Option<u16>,
+
+        /// Write answers for parts that have no entry yet instead of failing on them.
+        #[arg(long)]
+        record: bool,
+    },
+
+    /// Rebuild a day, run its tests and its real input on every change to its files.
+    ///
+    /// Watches the day's `src/` and `assets/` directories and compares each round's answers and
+    /// timings with the round before.
+    Watch(watch::WatchArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Puzzle year, e.g. 2022.
+    #[arg(required = true)]
+    year: Option<u16>,
+
+    /// Puzzle day; leave out when passing `--all`.
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    day: Option<u8>,
+
+    /// Run every registered day of the year in parallel and print a summary table.
+    #[arg(long)]
+    all: bool,
+
+    /// Only run the given part.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Read the puzzle input from this path instead of the day's `assets/input.txt`.
+    #[arg(long, conflicts_with = "all")]
+    input: Option<PathBuf>,
+
+    /// Fail on inputs with CRLF line endings, a byte order mark or a missing or extra final
+    /// newline instead of normalizing them.
+    #[arg(long)]
+    strict: bool,
+
+    #[command(flatten)]
+    run_all: run_all::RunAllArgs,
+
+    #[cfg(feature = "visualize")]
+    #[command(flatten)]
+    visualize: visualize::VisualizeArgs,
+}
+
+fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("Runner crate should live inside the workspace.")
+}
+
+/// A `cargo` command run from the workspace root, using the cargo that started the runner when
+/// there is one.
+fn cargo() -> Process {
+    let mut command = Process::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    command.current_dir(workspace_root());
+    command
+}
+
+/// Reads and normalizes a day's input from `path`, or without one from its `assets/input.txt`,
+/// or the copy built in with the `embed-inputs` feature. A missing or empty input gives
+/// `None`, while other I/O errors and, in `strict` mode, inputs that need normalizing fail.
+fn load_input(day: &Day, path: Option<&Path>, strict: bool) -> Result<Option<String>, InputError> {
+    #[cfg(feature = "embed-inputs")]
+    let loaded = match (path, day.embedded_input()) {
+        (Some(path), _) => input::read(path, strict),
+        (None, Some(embedded)) => input::prepare(embedded.to_string(), strict),
+        (None, None) => return Ok(None),
+    };
+    #[cfg(not(feature = "embed-inputs"))]
+    let loaded = input::read(
+        path.map_or_else(|| day.input_path(workspace_root()), Path::to_path_buf),
+        strict,
+    );
+    match loaded {
+        Ok(input) => Ok(Some(input).filter(|input| !input.is_empty())),
+        Err(InputError::Io(error)) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// [`load_input`] without strict mode, for commands that just run whatever input there is.
+fn read_input(day: &Day, path: Option<&Path>) -> Result<Option<String>, InputError> {
+    load_input(day, path, false)
+}
+
+/// Explains why [`load_input`] found nothing for `day`.
+fn missing_input(day: &Day, path: Option<&Path>) -> String {
+    match path {
+        Some(path) => format!("Should have an input file at path: {}", path.display()),
+        None if cfg!(feature = "embed-inputs") => format!(
+            "{} day {:02} was built without an input; pass one with --input.",
+            day.year, day.day
+        ),
+        None => format!(
+            "Should have an input file at path: {}",
+            day.input_path(workspace_root()).display()
+        ),
+    }
+}
+
+fn parts(part: Option<u8>) -> Vec<Part> {
+    match part.and_then(Part::from_number) {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    }
+}
+
+fn run_day(day: &Day, input: &str, part: Option<u8>) -> bool {
+    match (day.solution)().run(input, &parts(part)) {
+        Ok(solved) => {
+            for result in solved.parts {
+                if let Some(error) = result.answer.error() {
+                    eprintln!(
+                        "{} day {:02} part {}: {error}",
+                        day.year, day.day, result.part
+                    );
+                    return false;
+                }
+                println!("{}", result.answer);
+            }
+            true
+        }
+        Err(error) => {
+            eprintln!("{} day {:02}: {error}", day.year, day.day);
+            false
+        }
+    }
+}
+
+fn run(args: RunArgs) -> ExitCode {
+    let year = args.year.expect("Clap should require a year.");
+
+    if args.all {
+        return run_all::run_all(year, &parts(args.part), args.strict, args.run_all);
+    }
+
+    let day_number = args.day.expect("Clap should require a day without --all.");
+    let Some(day) = registry::find(year, day_number) else {
+        eprintln!("No solution registered for {year} day {day_number}.");
+        return ExitCode::FAILURE;
+    };
+    let input = match load_input(day, args.input.as_deref(), args.strict) {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("{} day {:02}: {error}", day.year, day.day);
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(input) = input else {
+        eprintln!("{}", missing_input(day, args.input.as_deref()));
+        return ExitCode::FAILURE;
+    };
+
+    #[cfg(feature = "visualize")]
+    if args.visualize.visualize.is_some() {
+        let part = args.part.and_then(Part::from_number);
+        let part = part.expect("Clap should require a part with --visualize.");
+        return visualize::visualize(day, &input, part, args.visualize);
+    }
+
+    if run_day(day, &input, args.part) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    // The dashboard draws over the whole terminal, so it keeps diagnostics out of the way.
+    if !matches!(cli.command, Some(Command::Dashboard(_))) {
+        common::log::init(cli.verbose);
+    }
+
+    match cli.command {
+        Some(Command::Bench(args)) => bench::bench(args),
+        Some(Command::Compare(args)) => compare::compare(args),
+        Some(Command::Dashboard(args)) => dashboard::dashboard(args),
+        Some(Command::Fetch(args)) => fetch::fetch(args),
+        Some(Command::New(args)) => scaffold::new_day(args),
+        Some(Command::Submit(args)) => submit::submit(args),
+        Some(Command::Verify { year, record }) => verify::verify(year, record),
+        Some(Command::Watch(args)) => watch::watch(args),
+        None => run(cli.run),
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "visualize")]
+use common::visual::DynVisualize;
+use common::DynSolution;
+
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    pub solution: fn() -> Box<dyn DynSolution>,
+}
+
+impl Day {
+    pub fn directory(&self, root: &Path) -> PathBuf {
+        root.join(self.year.to_string())
+            .join(format!("day_{:02}", self.day))
+    }
+
+    pub fn input_path(&self, root: &Path) -> PathBuf {
+        self.directory(root).join("assets").join("input.txt")
+    }
+
+    /// The input built into the runner, if this day had one when it was compiled.
+    #[cfg(feature = "embed-inputs")]
+    pub fn embedded_input(&self) -> Option<&'static str> {
+        embedded::INPUTS
+            .iter()
+            .find(|&&(year, day, _)| year == self.year && day == self.day)
+            .map(|&(_, _, input)| input)
+    }
+
+    /// The day's simulation, for days that can draw one.
+    #[cfg(feature = "visualize")]
+    pub fn visualization(&self) -> Option<Box<dyn DynVisualize>> {
+        Some(match (self.year, self.day) {
+            (2022, 9) => Box::new(aoc_2022_day_09::Day09::default()),
+            (2022, 12) => Box::new(aoc_2022_day_12::Day12),
+            (2022, 14) => Box::new(aoc_2022_day_14::Day14::default()),
+            (2022, 17) => Box::new(aoc_2022_day_17::Day17),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "embed-inputs")]
+mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+}
+
+macro_rules! registry {
+    ($($year:literal $day:literal => $solution:ty;)*) => {
+        pub const DAYS: &[Day] = &[
+            $(Day { year: $year, day: $day, solution: || Box::new(<$solution>::default()) },)*
+        ];
+    };
+}
+
+// One entry per day crate the runner depends on, generated by `build.rs`.
+include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+
+pub fn find(year: u16, day: u8) -> Option<&'static Day> {
+    DAYS.iter()
+        .find(|entry| entry.year == year && entry.day == day)
+}
+
+pub fn for_year(year: u16) -> impl Iterator<Item = &'static Day> {
+    DAYS.iter().filter(move |entry| entry.year == year)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::thread;
+    use std::time::Duration;
+
+    use common::{Answer, ParseError, Solution};
+
+    use super::Day;
+
+    /// Counts the lines of its input in part 1 and the bytes in part 2, except that `bad` fails
+    /// to parse, and inputs starting with `panic` panic in part 2, ones starting with `slow`
+    /// never finish it, and ones starting with `unsolved` or `failing` give no answer for it.
+    #[derive(Default)]
+    struct Misbehaving;
+
+    impl Solution for Misbehaving {
+        type Parsed<'a> = &'a str;
+        type Answer1 = usize;
+        type Answer2 = Answer;
+
+        fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+            match input {
+                "bad" => Err(ParseError::at(input, input, "a mode")),
+                _ => Ok(input),
+            }
+        }
+
+        fn part_1(&self, input: &&str) -> usize {
+            input.lines().count()
+        }
+
+        fn part_2(&self, input: &&str) -> Answer {
+            assert!(!input.starts_with("panic"), "part 2 is not done");
+            if input.starts_with("slow") {
+                loop {
+                    thread::sleep(Duration::from_millis(10));
+                }
+            }
+            if input.starts_with("unsolved") {
+                return Answer::Unsolved;
+            }
+            if input.starts_with("failing") {
+                return Answer::Error(String::from("no bytes worth counting"));
+            }
+            input.len().into()
+        }
+    }
+
+    /// A day for testing the commands that run days, solved by [`Misbehaving`].
+    pub static DAY: Day = Day {
+        year: 2000,
+        day: 1,
+        solution: || Box::new(Misbehaving),
+    };
+}
@@ -0,0 +1,371 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use common::{Answer, Part};
+use serde::Serialize;
+
+/// How running one part went, before comparing its answer with the answers file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    NoInput,
+    Error(String),
+    Panicked(String),
+    TimedOut,
+}
+
+/// The result of one part, or of a whole day when it failed before any part ran, like a
+/// missing input or a parse error.
+#[derive(Debug)]
+pub struct Row {
+    pub year: u16,
+    pub day: u8,
+    pub part: Option<Part>,
+    pub answer: Option<Answer>,
+    /// The accepted answer from the year's `answers.txt`, if there is one.
+    pub expected: Option<Answer>,
+    pub time: Option<Duration>,
+    pub status: Status,
+}
+
+impl Row {
+    /// Short outcome used by every format: `pass` and `fail` for answers that could be checked,
+    /// `ok` for ones that could not, `unsolved` for parts not solved yet, and otherwise what
+    /// went wrong. A part that is unsolved despite having an accepted answer fails.
+    pub fn outcome(&self) -> &'static str {
+        match (&self.status, &self.answer, &self.expected) {
+            (Status::Ok, Some(answer), Some(expected)) if answer == expected => "pass",
+            (Status::Ok, Some(_), Some(_)) => "fail",
+            (Status::Ok, Some(Answer::Unsolved), None) => "unsolved",
+            (Status::Ok, _, _) => "ok",
+            (Status::NoInput, _, _) => "no input",
+            (Status::Error(_), _, _) => "error",
+            (Status::Panicked(_), _, _) => "panicked",
+            (Status::TimedOut, _, _) => "timeout",
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        !matches!(self.outcome(), "pass" | "ok" | "unsolved" | "no input")
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match &self.status {
+            Status::Error(message) | Status::Panicked(message) => Some(message),
+            _ => None,
+        }
+    }
+
+    fn part_label(&self) -> String {
+        self.part
+            .map_or_else(|| String::from("-"), |part| part.to_string())
+    }
+}
+
+/// Everything one run produced, which the terminal table and the file reports are all
+/// rendered from.
+pub struct Report {
+    pub rows: Vec<Row>,
+}
+
+/// Pictures do not fit in a table cell, so they are summarised by their size.
+pub fn cell(answer: Option<&Answer>) -> String {
+    match answer {
+        Some(Answer::Grid(rows)) => format!("<{} rows>", rows.len()),
+        Some(answer) => answer.to_string(),
+        None => String::from("-"),
+    }
+}
+
+pub fn time_label(time: Option<Duration>) -> String {
+    time.map_or_else(|| String::from("-"), |time| format!("{time:.2?}"))
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    passed: usize,
+    failed: usize,
+    results: Vec<JsonRow<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonRow<'a> {
+    year: u16,
+    day: u8,
+    part: Option<u8>,
+    answer: Option<String>,
+    expected: Option<String>,
+    seconds: Option<f64>,
+    outcome: &'static str,
+    message: Option<&'a str>,
+}
+
+/// Escapes `text` for an attribute value. Line breaks and tabs are written as character
+/// references too, since parsers would otherwise turn them into plain spaces.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+        .replace('\r', "&#13;")
+        .replace('\t', "&#9;")
+}
+
+impl Report {
+    pub fn failed(&self) -> bool {
+        self.rows.iter().any(Row::failed)
+    }
+
+    /// The table printed at the end of a run, followed by the messages of failed days.
+    pub fn table(&self) -> String {
+        let answers: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| cell(row.answer.as_ref()))
+            .collect();
+        let width = answers.iter().map(String::len).max().unwrap_or(0).max(6);
+
+        let mut table = format!(
+            "{:<4} {:>3} {:>4} {:<width$} {:>12} status\n",
+            "year", "day", "part", "answer", "time"
+        );
+        for (row, answer) in self.rows.iter().zip(&answers) {
+            let _ = writeln!(
+                table,
+                "{:<4} {:>3} {:>4} {:<width$} {:>12} {}",
+                row.year,
+                row.day,
+                row.part_label(),
+                answer,
+                time_label(row.time),
+                row.outcome()
+            );
+        }
+        for row in &self.rows {
+            if let Some(message) = row.message() {
+                let part = row
+                    .part
+                    .map_or_else(String::new, |part| format!(" part {part}"));
+                let _ = write!(
+                    table,
+                    "\n{} day {:02}{part}: {message}\n",
+                    row.year, row.day
+                );
+            }
+        }
+        table
+    }
+
+    pub fn json(&self) -> String {
+        let results: Vec<JsonRow> = self
+            .rows
+            .iter()
+            .map(|row| JsonRow {
+                year: row.year,
+                day: row.day,
+                part: row.part.map(Part::number),
+                answer: row.answer.as_ref().map(Answer::to_string),
+                expected: row.expected.as_ref().map(Answer::to_string),
+                seconds: row.time.map(|time| time.as_secs_f64()),
+                outcome: row.outcome(),
+                message: row.message(),
+            })
+            .collect();
+        let report = JsonReport {
+            passed: self
+                .rows
+                .iter()
+                .filter(|row| row.outcome() == "pass")
+                .count(),
+            failed: self.rows.iter().filter(|row| row.failed()).count(),
+            results,
+        };
+        serde_json::to_string_pretty(&report).expect("Report should serialize to JSON.") + "\n"
+    }
+
+    pub fn markdown(&self) -> String {
+        let mut markdown = String::from(
+            "| Year | Day | Part | Answer | Time | Status |\n\
+             | ---: | --: | ---: | ------ | ---: | ------ |\n",
+        );
+        for row in &self.rows {
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} | {} | {} | {} |",
+                row.year,
+                row.day,
+                row.part_label(),
+                cell(row.answer.as_ref()).replace('|', "\\|"),
+                time_label(row.time),
+                row.outcome()
+            );
+        }
+        markdown
+    }
+
+    /// One test suite per year with a test case per part. Wrong answers count as failures,
+    /// crashes and timeouts as errors, and unsolved parts and days without input as skipped.
+    pub fn junit(&self) -> String {
+        let mut years: Vec<u16> = self.rows.iter().map(|row| row.year).collect();
+        years.dedup();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+        for year in years {
+            let rows: Vec<&Row> = self.rows.iter().filter(|row| row.year == year).collect();
+            let count = |outcomes: &[&str]| {
+                rows.iter()
+                    .filter(|row| outcomes.contains(&row.outcome()))
+                    .count()
+            };
+            let time: f64 = rows
+                .iter()
+                .filter_map(|row| row.time)
+                .map(|time| time.as_secs_f64())
+                .sum();
+            let _ = writeln!(
+                xml,
+                "  <testsuite name=\"aoc {year}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" \
+                 skipped=\"{}\" time=\"{time:.6}\">",
+                rows.len(),
+                count(&["fail"]),
+                count(&["error", "panicked", "timeout"]),
+                count(&["unsolved", "no input"]),
+            );
+            for row in rows {
+                let name = match row.part {
+                    Some(part) => format!("part {part}"),
+                    None => String::from("day"),
+                };
+                let _ = write!(
+                    xml,
+                    "    <testcase classname=\"aoc.{year}.day_{:02}\" name=\"{name}\" time=\"{:.6}\"",
+                    row.day,
+                    row.time.map_or(0.0, |time| time.as_secs_f64())
+                );
+                let body = match (row.outcome(), &row.expected) {
+                    ("fail", Some(expected)) => format!(
+                        "<failure message=\"expected {}, got {}\"/>",
+                        escape_xml(&expected.to_string()),
+                        escape_xml(&cell(row.answer.as_ref()))
+                    ),
+                    ("unsolved", _) => String::from("<skipped message=\"not solved yet\"/>"),
+                    ("no input", _) => String::from("<skipped message=\"no input\"/>"),
+                    ("timeout", _) => {
+                        String::from("<error type=\"timeout\" message=\"timed out\"/>")
+                    }
+                    (outcome @ ("error" | "panicked"), _) => format!(
+                        "<error type=\"{outcome}\" message=\"{}\"/>",
+                        escape_xml(row.message().unwrap_or_default())
+                    ),
+                    _ => String::new(),
+                };
+                if body.is_empty() {
+                    xml.push_str("/>\n");
+                } else {
+                    let _ = write!(xml, ">\n      {body}\n    </testcase>\n");
+                }
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        let row = |day, part, answer: Option<Answer>, expected: Option<Answer>, status| Row {
+            year: 2022,
+            day,
+            part,
+            answer,
+            expected,
+            time: Some(Duration::from_millis(2)),
+            status,
+        };
+        Report {
+            rows: vec![
+                row(
+                    1,
+                    Some(Part::One),
+                    Some(24000.into()),
+                    Some(24000.into()),
+                    Status::Ok,
+                ),
+                row(
+                    1,
+                    Some(Part::Two),
+                    Some(45000.into()),
+                    Some(4500.into()),
+                    Status::Ok,
+                ),
+                row(
+                    10,
+                    Some(Part::Two),
+                    Some(Answer::grid("#.\n.#")),
+                    None,
+                    Status::Ok,
+                ),
+                row(
+                    16,
+                    Some(Part::Two),
+                    None,
+                    None,
+                    Status::Panicked("a < b".into()),
+                ),
+                row(
+                    17,
+                    Some(Part::Two),
+                    Some(Answer::Unsolved),
+                    None,
+                    Status::Ok,
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn renders_markdown_rows() {
+        let markdown = report().markdown();
+        let lines: Vec<&str> = markdown.lines().collect();
+
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[2], "| 2022 | 1 | 1 | 24000 | 2.00ms | pass |");
+        assert_eq!(lines[4], "| 2022 | 10 | 2 | <2 rows> | 2.00ms | ok |");
+        assert_eq!(lines[6], "| 2022 | 17 | 2 | unsolved | 2.00ms | unsolved |");
+    }
+
+    #[test]
+    fn renders_junit_failures_and_errors() {
+        let junit = report().junit();
+
+        assert!(junit.contains("tests=\"5\" failures=\"1\" errors=\"1\" skipped=\"1\""));
+        assert!(junit.contains("<failure message=\"expected 4500, got 45000\"/>"));
+        assert!(junit.contains("<error type=\"panicked\" message=\"a &lt; b\"/>"));
+        assert!(junit.contains("<skipped message=\"not solved yet\"/>"));
+    }
+
+    #[test]
+    fn keeps_line_breaks_in_junit_attributes() {
+        assert_eq!(
+            escape_xml("left\r\n\t<right>"),
+            "left&#13;&#10;&#9;&lt;right&gt;"
+        );
+    }
+
+    #[test]
+    fn renders_json_results() {
+        let json: serde_json::Value = serde_json::from_str(&report().json()).unwrap();
+
+        assert_eq!(json["passed"], 1);
+        assert_eq!(json["failed"], 2);
+        assert_eq!(json["results"][1]["expected"], "4500");
+        assert_eq!(json["results"][2]["answer"], "#.\n.#");
+        assert_eq!(json["results"][3]["outcome"], "panicked");
+        assert_eq!(json["results"][4]["outcome"], "unsolved");
+    }
+}
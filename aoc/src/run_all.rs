@@ -0,0 +1,247 @@
+use std::any::Any;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use common::{Part, PartResult};
+
+use crate::answers::Answers;
+use crate::registry::{self, Day};
+use crate::report::{Report, Row, Status};
+use crate::{load_input, workspace_root};
+
+#[derive(Args)]
+pub struct RunAllArgs {
+    /// Number of days to run at once with `--all`; defaults to the number of CPUs.
+    #[arg(long, requires = "all")]
+    jobs: Option<usize>,
+
+    /// Seconds each day may take with `--all` before it is reported as timed out and left
+    /// running in the background.
+    #[arg(long, requires = "all", default_value_t = 60)]
+    timeout: u64,
+
+    /// Also write the results of `--all` as JSON to this path.
+    #[arg(long, requires = "all")]
+    json: Option<PathBuf>,
+
+    /// Also write the results of `--all` as a Markdown table to this path.
+    #[arg(long, requires = "all")]
+    markdown: Option<PathBuf>,
+
+    /// Also write the results of `--all` as JUnit XML to this path.
+    #[arg(long, requires = "all")]
+    junit: Option<PathBuf>,
+}
+
+enum Event {
+    Solved(PartResult),
+    Failed(Status),
+}
+
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+/// Solves `parts` of one day on a thread of its own, so that a panic only fails that day and a
+/// day that runs past `timeout` can be left behind. Rust cannot stop a thread, so an abandoned
+/// day keeps running until the process exits.
+pub fn run_day(day: &'static Day, input: String, parts: &[Part], timeout: Duration) -> Vec<Row> {
+    let row = |part, answer, time, status| Row {
+        year: day.year,
+        day: day.day,
+        part,
+        answer,
+        expected: None,
+        time,
+        status,
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let thread_parts = parts.to_vec();
+    thread::spawn(move || {
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            (day.solution)().run_with(&input, &thread_parts, &mut |result| {
+                let _ = sender.send(Event::Solved(result));
+            })
+        }));
+        let status = match outcome {
+            Ok(Ok(_)) => return,
+            Ok(Err(error)) => Status::Error(error.to_string()),
+            Err(payload) => Status::Panicked(panic_message(&*payload)),
+        };
+        let _ = sender.send(Event::Failed(status));
+    });
+
+    let deadline = Instant::now() + timeout;
+    let mut rows = Vec::new();
+    let mut remaining = parts.iter().copied();
+    let status = loop {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(Event::Solved(result)) => {
+                remaining.next();
+                let time = Some(result.time);
+                rows.push(match result.answer.error() {
+                    Some(error) => row(
+                        Some(result.part),
+                        None,
+                        time,
+                        Status::Error(error.to_string()),
+                    ),
+                    None => row(Some(result.part), Some(result.answer), time, Status::Ok),
+                });
+            }
+            Ok(Event::Failed(status)) => break status,
+            Err(RecvTimeoutError::Timeout) => break Status::TimedOut,
+            Err(RecvTimeoutError::Disconnected) => return rows,
+        }
+    };
+    match status {
+        Status::Error(_) => rows.push(row(None, None, None, status)),
+        status => rows.extend(remaining.map(|part| row(Some(part), None, None, status.clone()))),
+    }
+    rows
+}
+
+/// Runs every registered day of `year` on worker threads and prints one table row per part,
+/// checked against the year's answers file, then writes any requested reports. Panics and
+/// timeouts are reported per day instead of ending the run, and the command fails if any day
+/// did not finish cleanly or gave a wrong answer. In `strict` mode, days whose input would need
+/// normalizing fail with an error.
+pub fn run_all(year: u16, parts: &[Part], strict: bool, args: RunAllArgs) -> ExitCode {
+    let days: Vec<&'static Day> = registry::for_year(year).collect();
+    if days.is_empty() {
+        eprintln!("No days registered for {year}.");
+        return ExitCode::FAILURE;
+    }
+    let answers_path = Answers::path(workspace_root(), year);
+    let answers = match Answers::load(&answers_path) {
+        Ok(answers) => answers,
+        Err(error) => {
+            eprintln!("{}: {error}", answers_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let jobs = args
+        .jobs
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |jobs| jobs.get()));
+    let timeout = Duration::from_secs(args.timeout);
+
+    // Panics are reported in the table, so keep the default hook from printing them as well.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let queue = Mutex::new(days.into_iter());
+    let rows = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            scope.spawn(|| {
+                while let Some(day) = queue.lock().unwrap().next() {
+                    let day_row = |status| Row {
+                        year: day.year,
+                        day: day.day,
+                        part: None,
+                        answer: None,
+                        expected: None,
+                        time: None,
+                        status,
+                    };
+                    let day_rows = match load_input(day, None, strict) {
+                        Ok(Some(input)) => run_day(day, input, parts, timeout),
+                        Ok(None) => vec![day_row(Status::NoInput)],
+                        Err(error) => vec![day_row(Status::Error(error.to_string()))],
+                    };
+                    rows.lock().unwrap().extend(day_rows);
+                }
+            });
+        }
+    });
+    panic::set_hook(hook);
+
+    let mut rows = rows.into_inner().unwrap();
+    rows.sort_by_key(|row| (row.year, row.day, row.part));
+    for row in &mut rows {
+        if let Some(part) = row.part {
+            row.expected = answers.get(row.day, part).cloned();
+        }
+    }
+    let report = Report { rows };
+    print!("{}", report.table());
+
+    let mut failed = report.failed();
+    let outputs = [
+        (args.json, Report::json as fn(&Report) -> String),
+        (args.markdown, Report::markdown),
+        (args.junit, Report::junit),
+    ];
+    for (path, render) in outputs {
+        let Some(path) = path else {
+            continue;
+        };
+        if let Err(error) = fs::write(&path, render(&report)) {
+            eprintln!("{}: {error}", path.display());
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::tests::DAY;
+
+    fn statuses(input: &str) -> Vec<(Option<Part>, Status)> {
+        run_day(
+            &DAY,
+            input.to_string(),
+            &Part::ALL,
+            Duration::from_millis(200),
+        )
+        .into_iter()
+        .map(|row| (row.part, row.status))
+        .collect()
+    }
+
+    #[test]
+    fn keeps_finished_parts_when_a_later_one_fails() {
+        assert_eq!(
+            statuses("fine"),
+            vec![(Some(Part::One), Status::Ok), (Some(Part::Two), Status::Ok)]
+        );
+        assert_eq!(
+            statuses("panic"),
+            vec![
+                (Some(Part::One), Status::Ok),
+                (
+                    Some(Part::Two),
+                    Status::Panicked("part 2 is not done".into())
+                )
+            ]
+        );
+        assert_eq!(
+            statuses("slow"),
+            vec![
+                (Some(Part::One), Status::Ok),
+                (Some(Part::Two), Status::TimedOut)
+            ]
+        );
+        assert!(matches!(statuses("bad")[..], [(None, Status::Error(_))]));
+    }
+}
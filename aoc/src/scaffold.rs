@@ -0,0 +1,218 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Args;
+
+use crate::workspace_root;
+
+const CARGO_TEMPLATE: &str = include_str!("../templates/day/Cargo.toml.tmpl");
+const MAIN_TEMPLATE: &str = include_str!("../templates/day/main.rs.tmpl");
+const LIB_TEMPLATE: &str = include_str!("../templates/day/lib.rs.tmpl");
+const EXAMPLES_TEMPLATE: &str = include_str!("../templates/day/examples.toml.tmpl");
+
+#[derive(Args)]
+pub struct NewArgs {
+    /// Puzzle year of the new day.
+    year: u16,
+
+    /// Puzzle day to create.
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+
+    /// Print what would be created and changed without touching any files.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+enum Change {
+    CreateDir(PathBuf),
+    CreateFile(PathBuf, String),
+    UpdateFile(PathBuf, String),
+}
+
+impl Change {
+    fn describe(&self) -> String {
+        match self {
+            Change::CreateDir(path) => format!("create {}/", path.display()),
+            Change::CreateFile(path, _) => format!("create {}", path.display()),
+            Change::UpdateFile(path, _) => format!("update {}", path.display()),
+        }
+    }
+
+    fn apply(&self) -> io::Result<()> {
+        match self {
+            Change::CreateDir(path) => fs::create_dir_all(path),
+            Change::CreateFile(path, contents) | Change::UpdateFile(path, contents) => {
+                fs::write(path, contents)
+            }
+        }
+    }
+}
+
+fn render(template: &str, year: u16, day: u8) -> String {
+    template
+        .replace("{{year}}", &year.to_string())
+        .replace("{{day}}", &format!("{day:02}"))
+}
+
+fn crate_name(year: u16, day: u8) -> String {
+    format!("aoc_{year}_day_{day:02}")
+}
+
+/// Adds `{year}/day_*` to the workspace members unless the year is already covered.
+fn add_workspace_member(manifest: &str, year: u16) -> Option<String> {
+    let member = format!("\"{year}/day_*\",");
+    if manifest.contains(&member) {
+        return None;
+    }
+    let members_end = manifest.find("\n]")?;
+    let mut updated = manifest.to_string();
+    updated.insert_str(members_end, &format!("\n    {member}"));
+    Some(updated)
+}
+
+/// Inserts the day's path dependency into the runner manifest, keeping the day crates sorted.
+fn add_runner_dependency(manifest: &str, year: u16, day: u8) -> Option<String> {
+    let name = crate_name(year, day);
+    let line = format!("{name} = {{ path = \"../{year}/day_{day:02}\" }}");
+    insert_sorted_line(manifest, &line, |existing| {
+        existing
+            .starts_with("aoc_")
+            .then(|| existing.split(' ').next().unwrap_or_default() < name.as_str())
+    })
+}
+
+/// Inserts `line` after the last line for which `before` returns `Some(true)`, or before the
+/// first line for which it returns `Some(false)`. Returns `None` if the line is already present
+/// or no line is comparable.
+fn insert_sorted_line(
+    text: &str,
+    line: &str,
+    before: impl Fn(&str) -> Option<bool>,
+) -> Option<String> {
+    if text.lines().any(|existing| existing == line) {
+        return None;
+    }
+    let mut lines = text.lines().collect::<Vec<_>>();
+    let comparisons = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, existing)| before(existing).map(|before| (index, before)))
+        .collect::<Vec<_>>();
+    let index = match comparisons.iter().find(|(_, before)| !before) {
+        Some((index, _)) => *index,
+        None => comparisons.last()?.0 + 1,
+    };
+    lines.insert(index, line);
+    let mut updated = lines.join("\n");
+    if text.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
+fn plan(root: &Path, year: u16, day: u8) -> Result<Vec<Change>, String> {
+    let directory = root.join(year.to_string()).join(format!("day_{day:02}"));
+    if directory.exists() {
+        return Err(format!("{} already exists", directory.display()));
+    }
+
+    let mut changes = vec![
+        Change::CreateDir(directory.join("src")),
+        Change::CreateDir(directory.join("assets")),
+        Change::CreateFile(
+            directory.join("Cargo.toml"),
+            render(CARGO_TEMPLATE, year, day),
+        ),
+        Change::CreateFile(
+            directory.join("src").join("main.rs"),
+            render(MAIN_TEMPLATE, year, day),
+        ),
+        Change::CreateFile(
+            directory.join("src").join("lib.rs"),
+            render(LIB_TEMPLATE, year, day),
+        ),
+        Change::CreateFile(directory.join("assets").join("example.txt"), String::new()),
+        Change::CreateFile(
+            directory.join("assets").join("examples.toml"),
+            render(EXAMPLES_TEMPLATE, year, day),
+        ),
+    ];
+
+    let read = |path: &Path| {
+        fs::read_to_string(path).map_err(|error| format!("{}: {error}", path.display()))
+    };
+
+    let workspace_manifest = root.join("Cargo.toml");
+    if let Some(updated) = add_workspace_member(&read(&workspace_manifest)?, year) {
+        changes.push(Change::UpdateFile(workspace_manifest, updated));
+    }
+
+    let runner_manifest = root.join("aoc").join("Cargo.toml");
+    let updated = add_runner_dependency(&read(&runner_manifest)?, year, day).ok_or_else(|| {
+        format!(
+            "could not add {} to {}",
+            crate_name(year, day),
+            runner_manifest.display()
+        )
+    })?;
+    // The runner's build script registers every day crate it depends on.
+    changes.push(Change::UpdateFile(runner_manifest, updated));
+
+    Ok(changes)
+}
+
+/// Creates a new day crate from the templates and registers it with the workspace and runner.
+pub fn new_day(args: NewArgs) -> ExitCode {
+    let changes = match plan(workspace_root(), args.year, args.day) {
+        Ok(changes) => changes,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for change in changes.iter() {
+        if args.dry_run {
+            println!("would {}", change.describe());
+            continue;
+        }
+        if let Err(error) = change.apply() {
+            eprintln!("failed to {}: {error}", change.describe());
+            return ExitCode::FAILURE;
+        }
+        println!("{}", change.describe());
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runner_dependencies_stay_sorted() {
+        let manifest = "\
+[dependencies]
+clap = \"4\"
+aoc_2022_day_01 = { path = \"../2022/day_01\" }
+aoc_2023_day_01 = { path = \"../2023/day_01\" }
+";
+        let updated = add_runner_dependency(manifest, 2023, 2).unwrap();
+        assert!(updated.ends_with(
+            "aoc_2023_day_01 = { path = \"../2023/day_01\" }\naoc_2023_day_02 = { path = \"../2023/day_02\" }\n"
+        ));
+    }
+
+    #[test]
+    fn new_years_become_workspace_members() {
+        let manifest = "[workspace]\nmembers = [\n    \"aoc\",\n    \"2023/day_*\",\n]\n";
+        assert!(add_workspace_member(manifest, 2023).is_none());
+        assert_eq!(
+            add_workspace_member(manifest, 2024).unwrap(),
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"2023/day_*\",\n    \"2024/day_*\",\n]\n"
+        );
+    }
+}
@@ -0,0 +1,473 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+use common::{Answer, Part};
+
+use crate::answers::Answers;
+use crate::client::{self, ClientArgs};
+use crate::registry;
+use crate::{missing_input, read_input, workspace_root};
+
+#[derive(Args)]
+pub struct SubmitArgs {
+    /// Puzzle year.
+    year: u16,
+
+    /// Puzzle day.
+    day: u8,
+
+    /// Part to answer.
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: u8,
+
+    /// Answer to send; leave out to run the day on its input and send what it prints.
+    answer: Option<String>,
+
+    #[command(flatten)]
+    client: ClientArgs,
+}
+
+/// How the site judged a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    /// An answer was sent too recently; the site asks to wait this long.
+    Wait(Duration),
+    /// The part was solved before, so the site did not check the answer.
+    AlreadySolved,
+    Unrecognized,
+}
+
+impl Verdict {
+    /// Classifies the article text of the page returned after posting an answer.
+    fn classify(page: &str) -> Self {
+        if page.contains("That's the right answer") {
+            Verdict::Correct
+        } else if page.contains("That's not the right answer") {
+            if page.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if page.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Incorrect
+            }
+        } else if page.contains("You gave an answer too recently") {
+            Verdict::Wait(parse_time_left(page).unwrap_or(Duration::from_secs(60)))
+        } else if page.contains("You don't seem to be solving the right level") {
+            Verdict::AlreadySolved
+        } else {
+            Verdict::Unrecognized
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Incorrect => "wrong",
+            Verdict::Wait(_) => "wait",
+            Verdict::AlreadySolved => "already-solved",
+            Verdict::Unrecognized => "unrecognized",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "too-high" => Some(Verdict::TooHigh),
+            "too-low" => Some(Verdict::TooLow),
+            "wrong" => Some(Verdict::Incorrect),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Incorrect => write!(f, "wrong"),
+            Verdict::Wait(duration) => {
+                write!(f, "answered too recently, wait {}s", duration.as_secs())
+            }
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            Verdict::Unrecognized => write!(f, "unrecognized response"),
+        }
+    }
+}
+
+/// Reads "You have 1m 5s left to wait" from a too-recent response.
+fn parse_time_left(page: &str) -> Option<Duration> {
+    let start = page.find("You have ")? + "You have ".len();
+    let end = start + page[start..].find(" left to wait")?;
+    page[start..end]
+        .split_whitespace()
+        .map(|amount| {
+            let (number, unit) = amount.split_at(amount.len().checked_sub(1)?);
+            let number = number.parse::<u64>().ok()?;
+            match unit {
+                "h" => Some(number * 3600),
+                "m" => Some(number * 60),
+                "s" => Some(number),
+                _ => None,
+            }
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
+/// Reads the lockout after a wrong answer, "please wait one minute" or "please wait 5 minutes".
+fn parse_wrong_answer_lockout(page: &str) -> Option<Duration> {
+    let page = page.to_lowercase();
+    let start = page.find("please wait ")? + "please wait ".len();
+    let mut words = page[start..].split_whitespace();
+    let minutes = match words.next()? {
+        "one" => 1,
+        number => number.parse().ok()?,
+    };
+    words
+        .next()?
+        .starts_with("minute")
+        .then(|| Duration::from_secs(minutes * 60))
+}
+
+type Key = (u16, u8, Part);
+
+/// Wrong guesses from earlier submissions and the pending cooldown, kept so that the site is
+/// never asked about an answer it already rejected or before it accepts another one. The site
+/// makes the whole account wait, whichever puzzle the wrong answer was for.
+///
+/// Stored one `year day part verdict answer` line per rejected guess, followed by an
+/// `until` line with the Unix time the cooldown ends.
+#[derive(Debug, Default)]
+struct Submissions {
+    rejected: BTreeMap<Key, Vec<(Verdict, String)>>,
+    cooldown: Option<u64>,
+}
+
+impl Submissions {
+    fn default_path() -> PathBuf {
+        client::account_dir().join("submissions.txt")
+    }
+
+    fn load(path: &Path) -> io::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(error),
+        };
+        Ok(Self::parse(&contents))
+    }
+
+    fn parse(contents: &str) -> Self {
+        let mut submissions = Self::default();
+        for line in contents.lines() {
+            if let Some(until) = line.strip_prefix("until ") {
+                submissions.cooldown = until.parse().ok();
+                continue;
+            }
+            let mut fields = line.splitn(5, ' ');
+            let (Some(year), Some(day), Some(part), Some(kind), Some(value)) = (
+                fields.next().and_then(|year| year.parse().ok()),
+                fields.next().and_then(|day| day.parse().ok()),
+                fields
+                    .next()
+                    .and_then(|part| part.parse().ok())
+                    .and_then(Part::from_number),
+                fields.next(),
+                fields.next(),
+            ) else {
+                continue;
+            };
+            if let Some(verdict) = Verdict::from_name(kind) {
+                submissions
+                    .rejected
+                    .entry((year, day, part))
+                    .or_default()
+                    .push((verdict, value.to_string()));
+            }
+        }
+        submissions
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        for ((year, day, part), rejected) in self.rejected.iter() {
+            for (verdict, answer) in rejected {
+                contents.push_str(&format!(
+                    "{year} {day} {part} {} {answer}\n",
+                    verdict.name()
+                ));
+            }
+        }
+        if let Some(until) = self.cooldown {
+            contents.push_str(&format!("until {until}\n"));
+        }
+        fs::write(path, contents)
+    }
+
+    /// Explains why `answer` should not be sent, if an earlier response already rules it out.
+    fn refusal(&self, key: Key, answer: &str, now: u64) -> Option<String> {
+        if let Some(until) = self.cooldown {
+            if until > now {
+                return Some(format!("still cooling down, wait {}s", until - now));
+            }
+        }
+
+        let rejected = self
+            .rejected
+            .get(&key)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if let Some((verdict, _)) = rejected.iter().find(|(_, guess)| guess == answer) {
+            return Some(format!("{answer} was already rejected ({verdict})"));
+        }
+
+        // Too high and too low hints bound every later numeric guess as well.
+        let number = answer.parse::<i128>().ok()?;
+        rejected.iter().find_map(|(verdict, guess)| {
+            let guess = guess.parse::<i128>().ok()?;
+            match verdict {
+                Verdict::TooHigh if number >= guess => {
+                    Some(format!("{answer} is not below {guess}, which was too high"))
+                }
+                Verdict::TooLow if number <= guess => {
+                    Some(format!("{answer} is not above {guess}, which was too low"))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Remembers a response so that later submissions can be checked against it.
+    fn record(&mut self, key: Key, answer: &str, verdict: Verdict, page: &str, now: u64) {
+        let cooldown = match verdict {
+            Verdict::Wait(duration) => Some(duration),
+            Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect => {
+                self.rejected
+                    .entry(key)
+                    .or_default()
+                    .push((verdict, answer.to_string()));
+                parse_wrong_answer_lockout(page)
+            }
+            _ => None,
+        };
+        self.cooldown = cooldown.map(|cooldown| now + cooldown.as_secs());
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn compute_answer(year: u16, day: u8, part: Part) -> Result<String, String> {
+    let day = registry::find(year, day)
+        .ok_or_else(|| format!("No solution registered for {year} day {day}."))?;
+    let input = read_input(day, None)
+        .map_err(|error| format!("{} day {:02}: {error}", day.year, day.day))?
+        .ok_or_else(|| missing_input(day, None))?;
+    let solved = (day.solution)()
+        .run(&input, &[part])
+        .map_err(|error| error.to_string())?;
+    let label = format!("{} day {:02} part {part}", day.year, day.day);
+    match &solved.parts[0].answer {
+        Answer::Error(error) => Err(format!("{label}: {error}")),
+        Answer::Unsolved => Err(format!("{label} is not solved yet.")),
+        answer => Ok(answer.to_string()),
+    }
+}
+
+/// Sends one answer, unless an earlier response already rules it out, and records the verdict.
+/// Correct answers are added to the year's `answers.txt`.
+pub fn submit(args: SubmitArgs) -> ExitCode {
+    let part = Part::from_number(args.part).expect("Clap should only accept parts 1 and 2.");
+    let key = (args.year, args.day, part);
+
+    let answer = match args.answer {
+        Some(answer) => answer,
+        None => match compute_answer(args.year, args.day, part) {
+            Ok(answer) => answer,
+            Err(error) => {
+                eprintln!("{error}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+    let answer = answer.trim();
+    if answer.is_empty() || answer.contains('\n') {
+        eprintln!("Answer {answer:?} has to be a single line; pass the letters it shows instead.");
+        return ExitCode::FAILURE;
+    }
+
+    let answers_path = Answers::path(workspace_root(), args.year);
+    let mut answers = match Answers::load(&answers_path) {
+        Ok(answers) => answers,
+        Err(error) => {
+            eprintln!("{}: {error}", answers_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(correct) = answers.get(args.day, part) {
+        if *correct == Answer::from(answer) {
+            println!("{answer} is already recorded as correct.");
+            return ExitCode::SUCCESS;
+        }
+        eprintln!("Already solved with {correct}; refusing to send {answer}.");
+        return ExitCode::FAILURE;
+    }
+
+    let submissions_path = Submissions::default_path();
+    let mut submissions = match Submissions::load(&submissions_path) {
+        Ok(submissions) => submissions,
+        Err(error) => {
+            eprintln!("{}: {error}", submissions_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(reason) = submissions.refusal(key, answer, unix_now()) {
+        eprintln!("Not submitting: {reason}.");
+        return ExitCode::FAILURE;
+    }
+
+    let page = match args
+        .client
+        .client()
+        .and_then(|client| client.submit(args.year, args.day, part, answer))
+    {
+        Ok(page) => page,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let verdict = Verdict::classify(&page);
+    println!(
+        "{} day {:02} part {part}: {answer} is {verdict}",
+        args.year, args.day
+    );
+
+    submissions.record(key, answer, verdict, &page, unix_now());
+    if let Err(error) = submissions.save(&submissions_path) {
+        eprintln!("{}: {error}", submissions_path.display());
+    }
+
+    match verdict {
+        Verdict::Correct => {
+            answers.insert(args.day, part, Answer::from(answer));
+            if let Err(error) = answers.save(&answers_path) {
+                eprintln!("{}: {error}", answers_path.display());
+                return ExitCode::FAILURE;
+            }
+            ExitCode::SUCCESS
+        }
+        Verdict::AlreadySolved => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::{mock_server, test_client};
+
+    const KEY: Key = (2022, 1, Part::One);
+
+    #[test]
+    fn classifies_responses() {
+        let cases = [
+            (
+                "<p>That's the right answer! You are one gold star closer.</p>",
+                Verdict::Correct,
+            ),
+            (
+                "<p>That's not the right answer; your answer is too high. Please wait one minute \
+                 before trying again.</p>",
+                Verdict::TooHigh,
+            ),
+            (
+                "<p>That's not the right answer; your answer is too low.</p>",
+                Verdict::TooLow,
+            ),
+            (
+                "<p>That's not the right answer. If you're stuck...</p>",
+                Verdict::Incorrect,
+            ),
+            (
+                "<p>You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again. You have 1m 5s left to wait.</p>",
+                Verdict::Wait(Duration::from_secs(65)),
+            ),
+            (
+                "<p>You don't seem to be solving the right level. Did you already complete it?</p>",
+                Verdict::AlreadySolved,
+            ),
+            ("<html>Maintenance</html>", Verdict::Unrecognized),
+        ];
+        for (page, verdict) in cases {
+            assert_eq!(Verdict::classify(page), verdict, "{page}");
+        }
+    }
+
+    #[test]
+    fn refuses_rejected_and_out_of_bounds_guesses() {
+        let mut submissions = Submissions::default();
+        let page = "That's not the right answer; your answer is too high. Please wait 5 minutes";
+        submissions.record(KEY, "500", Verdict::TooHigh, page, 1000);
+        submissions.record(KEY, "100", Verdict::TooLow, "", 1000);
+        submissions.record(KEY, "abc", Verdict::Incorrect, "", 1000);
+
+        assert!(submissions.refusal(KEY, "abc", 1000).is_some());
+        assert!(submissions.refusal(KEY, "600", 1000).is_some());
+        assert!(submissions.refusal(KEY, "50", 1000).is_some());
+        assert!(submissions.refusal(KEY, "300", 1000).is_none());
+        assert!(submissions
+            .refusal((2022, 1, Part::Two), "600", 1000)
+            .is_none());
+    }
+
+    #[test]
+    fn remembers_cooldowns_across_saves() {
+        let mut submissions = Submissions::default();
+        let page = "That's not the right answer; your answer is too high. Please wait 5 minutes";
+        submissions.record(KEY, "500", Verdict::TooHigh, page, 1000);
+
+        let path = std::env::temp_dir().join("aoc_submissions_round_trip.txt");
+        submissions.save(&path).unwrap();
+        let loaded = Submissions::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(loaded.refusal(KEY, "300", 1299).is_some());
+        assert!(loaded.refusal((2023, 5, Part::Two), "1", 1299).is_some());
+        assert!(loaded.refusal(KEY, "300", 1300).is_none());
+        assert!(loaded.refusal(KEY, "500", 1300).is_some());
+    }
+
+    #[test]
+    fn posts_the_level_and_answer() {
+        let (base_url, request) = mock_server("200 OK", "<p>That's the right answer!</p>");
+        let client = test_client(&base_url, "submit");
+
+        let page = client.submit(2022, 1, Part::Two, "45000").unwrap();
+        assert_eq!(Verdict::classify(&page), Verdict::Correct);
+        let request = request.recv().unwrap();
+        assert!(request.starts_with("post /2022/day/1/answer http/1.1"));
+        assert!(request.ends_with("level=2&answer=45000"));
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::BTreeSet;
+use std::process::ExitCode;
+
+use common::{Answer, Part};
+
+use crate::answers::Answers;
+use crate::registry::{self, Day};
+use crate::{read_input, workspace_root};
+
+#[derive(Default)]
+struct Tally {
+    correct: usize,
+    mismatched: usize,
+    missing: usize,
+    unsolved: usize,
+    recorded: usize,
+    errors: usize,
+}
+
+/// Runs every registered day that has an input and compares each part against the year's
+/// answers file, failing on any mismatch, missing entry or parse error.
+///
+/// With `record`, missing entries are filled in from the current results instead of counting
+/// as failures. Existing entries are never overwritten. Parts that are not solved yet and have
+/// no entry are counted on their own, neither failing nor being recorded.
+pub fn verify(year: Option<u16>, record: bool) -> ExitCode {
+    let mut tally = Tally::default();
+    let years = registry::DAYS
+        .iter()
+        .map(|day| day.year)
+        .filter(|&day_year| year.is_none_or(|year| day_year == year))
+        .collect::<BTreeSet<u16>>();
+
+    for year in years {
+        let path = Answers::path(workspace_root(), year);
+        let mut answers = match Answers::load(&path) {
+            Ok(answers) => answers,
+            Err(error) => {
+                eprintln!("{}: {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        };
+
+        let recorded = tally.recorded;
+        for day in registry::for_year(year) {
+            verify_day(day, &mut answers, record, &mut tally);
+        }
+
+        if tally.recorded > recorded {
+            if let Err(error) = answers.save(&path) {
+                eprintln!("{}: failed to write answers: {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    println!(
+        "{} correct, {} mismatched, {} missing, {} unsolved, {} recorded, {} errors",
+        tally.correct,
+        tally.mismatched,
+        tally.missing,
+        tally.unsolved,
+        tally.recorded,
+        tally.errors
+    );
+    if tally.mismatched + tally.missing + tally.errors == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Formats an answer for the report, moving pictures onto their own indented lines.
+fn show(answer: &Answer) -> String {
+    if answer.is_multi_line() {
+        let rows = answer.to_string();
+        rows.lines().map(|row| format!("\n    {row}")).collect()
+    } else {
+        answer.to_string()
+    }
+}
+
+fn verify_day(day: &Day, answers: &mut Answers, record: bool, tally: &mut Tally) {
+    let label = format!("{} day {:02}", day.year, day.day);
+    let input = match read_input(day, None) {
+        Ok(Some(input)) => input,
+        Ok(None) => {
+            println!("{label}: no input");
+            return;
+        }
+        Err(error) => {
+            println!("{label}: {error}");
+            tally.errors += 1;
+            return;
+        }
+    };
+    let solved = match (day.solution)().run(&input, &Part::ALL) {
+        Ok(solved) => solved,
+        Err(error) => {
+            println!("{label}: {error}");
+            tally.errors += 1;
+            return;
+        }
+    };
+
+    for result in solved.parts {
+        let label = format!("{label} part {}", result.part);
+        if let Some(error) = result.answer.error() {
+            println!("{label}: {error}");
+            tally.errors += 1;
+            continue;
+        }
+        match answers.get(day.day, result.part) {
+            Some(expected) if *expected == result.answer => {
+                println!("{label}: ok");
+                tally.correct += 1;
+            }
+            Some(expected) => {
+                println!("{label}: mismatch");
+                println!("  expected: {}", show(expected));
+                println!("  got:      {}", show(&result.answer));
+                tally.mismatched += 1;
+            }
+            None if result.answer.is_unsolved() => {
+                println!("{label}: not solved yet");
+                tally.unsolved += 1;
+            }
+            None if record => {
+                println!("{label}: recorded {}", show(&result.answer));
+                answers.insert(day.day, result.part, result.answer);
+                tally.recorded += 1;
+            }
+            None => {
+                println!("{label}: missing answer (got {})", show(&result.answer));
+                tally.missing += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, ValueEnum};
+use common::visual::{Backend, Gif, Recorder, Terminal};
+use common::Part;
+
+use crate::registry::Day;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Output {
+    /// Play the animation in place in the terminal.
+    Terminal,
+    /// Write an animated GIF to `--output`.
+    Gif,
+}
+
+#[derive(Args)]
+pub struct VisualizeArgs {
+    /// Draw the day's simulation for the part given with `--part` instead of printing answers.
+    #[arg(long, value_enum, requires = "part", conflicts_with = "all")]
+    pub visualize: Option<Output>,
+
+    /// Frames per second of the animation.
+    #[arg(long, requires = "visualize", default_value_t = 30)]
+    fps: u32,
+
+    /// Only draw every n-th step of the simulation, to shorten long animations.
+    #[arg(long, requires = "visualize", default_value_t = 1)]
+    every: usize,
+
+    /// Path to write the GIF to with `--visualize gif`.
+    #[arg(long, required_if_eq("visualize", "gif"))]
+    output: Option<PathBuf>,
+
+    /// Width and height in pixels of each cell of the GIF.
+    #[arg(long, requires = "output", default_value_t = 4)]
+    scale: usize,
+}
+
+/// Replays `part` of `day` on `input` into the backend chosen by `args`.
+pub fn visualize(day: &Day, input: &str, part: Part, args: VisualizeArgs) -> ExitCode {
+    let Some(solution) = day.visualization() else {
+        eprintln!("{} day {:02} has no visualization.", day.year, day.day);
+        return ExitCode::FAILURE;
+    };
+    let gif_path = match args.visualize {
+        Some(Output::Gif) => args.output.as_deref(),
+        _ => None,
+    };
+    let backend: Box<dyn Backend> = match gif_path {
+        Some(path) => match Gif::create(path, args.fps, args.scale) {
+            Ok(gif) => Box::new(gif),
+            Err(error) => {
+                eprintln!("Failed to create {}: {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Box::new(Terminal::new(args.fps)),
+    };
+
+    let mut recorder = Recorder::new(backend, args.every);
+    if let Err(error) = solution.visualize(input, part, &mut recorder) {
+        eprintln!("{} day {:02}: {error}", day.year, day.day);
+        return ExitCode::FAILURE;
+    }
+    match (recorder.finish(), gif_path) {
+        (Ok(frames), Some(path)) => {
+            eprintln!("Wrote {frames} frames to {}.", path.display());
+            ExitCode::SUCCESS
+        }
+        (Ok(_), None) => ExitCode::SUCCESS,
+        (Err(error), _) => {
+            eprintln!("Failed to draw the visualization: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
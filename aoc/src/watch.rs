@@ -0,0 +1,443 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use clap::Args;
+use common::{Answer, Part};
+
+use crate::registry::{self, Day};
+use crate::{cargo, workspace_root};
+
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Puzzle year of the day to watch.
+    year: u16,
+
+    /// Puzzle day to watch.
+    day: u8,
+
+    /// Read the puzzle input from this path instead of the day's `assets/input.txt`.
+    #[arg(long)]
+    input: Option<PathBuf>,
+
+    /// Seconds each part may take on the real input before it is stopped.
+    #[arg(long, default_value_t = 60)]
+    timeout: u64,
+}
+
+/// Modification time and size of every file below the watched directories.
+type Snapshot = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+fn snapshot(directories: &[PathBuf]) -> Snapshot {
+    let mut files = Snapshot::new();
+    let mut pending = directories.to_vec();
+    while let Some(directory) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if let Ok(modified) = metadata.modified() {
+                files.insert(entry.path(), (modified, metadata.len()));
+            }
+        }
+    }
+    files
+}
+
+/// Files that were added, removed or modified between two snapshots.
+fn changed_files(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let mut changed: Vec<PathBuf> = after
+        .iter()
+        .filter(|(path, state)| before.get(*path) != Some(state))
+        .map(|(path, _)| path.clone())
+        .collect();
+    changed.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .cloned(),
+    );
+    changed.sort();
+    changed
+}
+
+/// Totals from the output of `cargo test`, with the names of the tests that failed.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TestSummary {
+    passed: usize,
+    failed: usize,
+    failures: Vec<String>,
+}
+
+fn parse_test_output(output: &str) -> Option<TestSummary> {
+    let mut summary = None::<TestSummary>;
+    let mut in_failures = false;
+    for line in output.lines() {
+        if let Some(totals) = line.strip_prefix("test result: ") {
+            let summary = summary.get_or_insert_with(TestSummary::default);
+            for total in totals.split(". ").flat_map(|part| part.split("; ")) {
+                match total.split_once(' ') {
+                    Some((count, "passed")) => summary.passed += count.parse().unwrap_or(0),
+                    Some((count, "failed")) => summary.failed += count.parse().unwrap_or(0),
+                    _ => {}
+                }
+            }
+            in_failures = false;
+        } else if line == "failures:" {
+            in_failures = true;
+        } else if let Some(name) = line.strip_prefix("    ").filter(|_| in_failures) {
+            let summary = summary.get_or_insert_with(TestSummary::default);
+            summary.failures.push(name.to_string());
+        }
+    }
+    summary
+}
+
+/// How one part did on the real input.
+#[derive(Debug, Clone, PartialEq)]
+struct PartRun {
+    answer: Result<Answer, String>,
+    time: Duration,
+}
+
+/// What one round of building, testing and running produced.
+#[derive(Debug, Default)]
+struct Round {
+    tests: Option<TestSummary>,
+    parts: BTreeMap<Part, PartRun>,
+}
+
+/// Where cargo puts the day's release binary.
+fn binary_path(day: &Day) -> PathBuf {
+    let target = env::var_os("CARGO_TARGET_DIR")
+        .map_or_else(|| workspace_root().join("target"), PathBuf::from);
+    let name = format!(
+        "aoc_{}_day_{:02}{}",
+        day.year,
+        day.day,
+        env::consts::EXE_SUFFIX
+    );
+    target.join("release").join(name)
+}
+
+/// Runs the day's binary on one part of `input`, stopping it after `timeout`. The time
+/// includes starting the process and parsing the input.
+fn run_part(day: &Day, input: &Path, part: Part, timeout: Duration) -> PartRun {
+    let mut command = Command::new(binary_path(day));
+    command.args(["--part", &part.to_string()]).arg(input);
+    run_answer(command, timeout)
+}
+
+/// Reads all of `pipe` on a thread of its own, so that a child filling one pipe is never left
+/// waiting on a reader that is busy elsewhere.
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut output);
+        }
+        output
+    })
+}
+
+/// Runs `command`, which prints one answer, stopping it after `timeout`.
+fn run_answer(mut command: Command, timeout: Duration) -> PartRun {
+    let start = Instant::now();
+    let child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(error) => {
+            return PartRun {
+                answer: Err(error.to_string()),
+                time: start.elapsed(),
+            };
+        }
+    };
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) if start.elapsed() > timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break Err(format!("timed out after {timeout:?}"));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(5)),
+            Err(error) => break Err(error.to_string()),
+        }
+    };
+    let time = start.elapsed();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    let answer = match status {
+        Ok(status) if status.success() => {
+            let Ok(answer) = stdout.trim_end_matches('\n').parse();
+            Ok(answer)
+        }
+        Ok(_) => Err(stderr.trim_end().to_string()),
+        Err(error) => Err(error),
+    };
+    PartRun { answer, time }
+}
+
+/// Builds the day, runs its tests and then its real input, printing each step's outcome.
+fn run_round(day: &Day, input: &Path, timeout: Duration) -> Round {
+    let package = format!("aoc_{}_day_{:02}", day.year, day.day);
+    let mut round = Round::default();
+
+    let build = cargo()
+        .args(["build", "--release", "--quiet", "-p", &package])
+        .output();
+    match build {
+        Ok(output) if output.status.success() => println!("build: ok"),
+        Ok(output) => {
+            println!("build: failed");
+            print!("{}", String::from_utf8_lossy(&output.stderr));
+            return round;
+        }
+        Err(error) => {
+            println!("build: failed to run cargo: {error}");
+            return round;
+        }
+    }
+
+    let tests = cargo().args(["test", "--quiet", "-p", &package]).output();
+    match tests {
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            round.tests = parse_test_output(&stdout);
+            if round.tests.is_none() {
+                println!("tests: failed to build");
+                print!("{}", String::from_utf8_lossy(&output.stderr));
+            }
+        }
+        Err(error) => println!("tests: failed to run cargo: {error}"),
+    }
+
+    if input.exists() {
+        for part in Part::ALL {
+            round
+                .parts
+                .insert(part, run_part(day, input, part, timeout));
+        }
+    } else {
+        println!("input: nothing at {}", input.display());
+    }
+    round
+}
+
+fn time_change(time: Duration, previous: Duration) -> String {
+    let change = (time.as_secs_f64() / previous.as_secs_f64().max(1e-9) - 1.0) * 100.0;
+    format!("{time:.2?} (was {previous:.2?}, {change:+.0}%)")
+}
+
+/// Describes `round`, pointing out what differs from the round before it.
+fn describe(round: &Round, previous: Option<&Round>) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(tests) = &round.tests {
+        let mut line = format!("tests: {} passed, {} failed", tests.passed, tests.failed);
+        match previous.and_then(|previous| previous.tests.as_ref()) {
+            Some(before) if (before.passed, before.failed) != (tests.passed, tests.failed) => {
+                line += &format!(" (was {} passed, {} failed)", before.passed, before.failed);
+            }
+            _ => {}
+        }
+        lines.push(line);
+        lines.extend(
+            tests
+                .failures
+                .iter()
+                .map(|name| format!("  failed: {name}")),
+        );
+    }
+
+    for (part, run) in &round.parts {
+        let before = previous.and_then(|previous| previous.parts.get(part));
+        let answer = match &run.answer {
+            Ok(answer) if answer.is_multi_line() => format!("\n{answer}\n"),
+            Ok(answer) => answer.to_string(),
+            Err(error) => format!("error: {error}"),
+        };
+        let mut line = format!("part {part}: {answer}");
+        match before.map(|before| &before.answer) {
+            Some(before) if *before == run.answer => line += "  (unchanged)",
+            Some(Ok(before)) if !before.is_multi_line() => line += &format!("  (was {before})"),
+            Some(_) => line += "  (changed)",
+            None => {}
+        }
+        let time = match before {
+            Some(before) if before.answer.is_ok() && run.answer.is_ok() => {
+                time_change(run.time, before.time)
+            }
+            _ => format!("{:.2?}", run.time),
+        };
+        lines.push(format!("{line}  {time}"));
+    }
+    lines
+}
+
+/// Rebuilds a day, runs its tests and its real input whenever a file in its `src/` or
+/// `assets/` directory changes, and reports how the answers and timings moved.
+pub fn watch(args: WatchArgs) -> ExitCode {
+    let Some(day) = registry::find(args.year, args.day) else {
+        eprintln!("No solution registered for {} day {}.", args.year, args.day);
+        return ExitCode::FAILURE;
+    };
+    let directory = day.directory(workspace_root());
+    let watched = [directory.join("src"), directory.join("assets")];
+    let input = args
+        .input
+        .unwrap_or_else(|| day.input_path(workspace_root()));
+    let timeout = Duration::from_secs(args.timeout);
+
+    println!(
+        "Watching {} for changes, press Ctrl-C to stop.",
+        directory.display()
+    );
+    let mut files = snapshot(&watched);
+    let mut previous: Option<Round> = None;
+    loop {
+        let round = run_round(day, &input, timeout);
+        for line in describe(&round, previous.as_ref()) {
+            println!("{line}");
+        }
+        previous = Some(round);
+
+        // Wait for a change, then for the files to settle, since editors often save in steps.
+        let changed = loop {
+            thread::sleep(Duration::from_millis(300));
+            let current = snapshot(&watched);
+            let changed = changed_files(&files, &current);
+            if !changed.is_empty() {
+                thread::sleep(Duration::from_millis(100));
+                files = snapshot(&watched);
+                break changed;
+            }
+        };
+        let names: Vec<String> = changed
+            .iter()
+            .map(|path| {
+                let path = path.strip_prefix(&directory).unwrap_or(path);
+                path.display().to_string()
+            })
+            .collect();
+        println!("\n--- {} changed ---", names.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_added_modified_and_removed_files() {
+        let directory = env::temp_dir().join("aoc_watch_snapshot");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(directory.join("src")).unwrap();
+        fs::write(directory.join("src/lib.rs"), "fn main() {}").unwrap();
+        fs::write(directory.join("notes.txt"), "").unwrap();
+        let before = snapshot(std::slice::from_ref(&directory));
+
+        fs::write(directory.join("src/lib.rs"), "fn main() { todo!() }").unwrap();
+        fs::write(directory.join("input.txt"), "1\n").unwrap();
+        fs::remove_file(directory.join("notes.txt")).unwrap();
+        let after = snapshot(std::slice::from_ref(&directory));
+
+        assert_eq!(
+            changed_files(&before, &after),
+            [
+                directory.join("input.txt"),
+                directory.join("notes.txt"),
+                directory.join("src/lib.rs"),
+            ]
+        );
+        assert_eq!(changed_files(&after, &after), Vec::<PathBuf>::new());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_output_larger_than_a_pipe_while_the_child_runs() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "head -c 1000000 /dev/zero | tr '\\0' x >&2; echo 42"]);
+        let run = run_answer(command, Duration::from_secs(10));
+
+        assert_eq!(run.answer, Ok(Answer::from(42)));
+    }
+
+    #[test]
+    fn sums_test_results_and_collects_failures() {
+        let output = "\n\
+            running 2 tests\n\
+            .F\n\
+            failures:\n\
+            \n\
+            ---- examples::example::part_2 stdout ----\n\
+            assertion `left == right` failed\n\
+            \n\
+            failures:\n    \
+                examples::example::part_2\n\
+            \n\
+            test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out\n\
+            \n\
+            running 0 tests\n\
+            test result: ok. 0 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out\n";
+
+        assert_eq!(
+            parse_test_output(output),
+            Some(TestSummary {
+                passed: 1,
+                failed: 1,
+                failures: vec!["examples::example::part_2".into()],
+            })
+        );
+        assert_eq!(parse_test_output("error[E0308]: mismatched types"), None);
+    }
+
+    #[test]
+    fn compares_answers_and_timings_with_the_last_round() {
+        let run = |answer: &str, millis| PartRun {
+            answer: Ok(answer.into()),
+            time: Duration::from_millis(millis),
+        };
+        let round = |tests: (usize, usize), parts: [PartRun; 2]| Round {
+            tests: Some(TestSummary {
+                passed: tests.0,
+                failed: tests.1,
+                failures: Vec::new(),
+            }),
+            parts: Part::ALL.into_iter().zip(parts).collect(),
+        };
+        let before = round((1, 1), [run("24", 10), run("part2", 1)]);
+        let after = round((2, 0), [run("24", 5), run("93", 20)]);
+
+        assert_eq!(
+            describe(&after, Some(&before)),
+            [
+                "tests: 2 passed, 0 failed (was 1 passed, 1 failed)",
+                "part 1: 24  (unchanged)  5.00ms (was 10.00ms, -50%)",
+                "part 2: 93  (was part2)  20.00ms (was 1.00ms, +1900%)",
+            ]
+        );
+        assert_eq!(
+            describe(&after, None)[1..],
+            ["part 1: 24  5.00ms", "part 2: 93  20.00ms"]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Heap allocation accounting for the parse and solve phases.
+//!
+//! With the `count-allocations` feature on, this module installs a global allocator that wraps
+//! [`System`] and counts every allocation made by the current thread, and [`measure`] reports
+//! what a closure allocated. Without the feature [`measure`] just runs the closure, so callers do
+//! not need their own `cfg`s. Enable it for a day binary with
+//! `cargo run --release -p aoc_2022_day_16 --features common/count-allocations -- input.txt`,
+//! or for the runner with `--features count-allocations`.
+//!
+//! Counters are kept per thread so that days running side by side do not mix their numbers.
+//! Memory freed on another thread than the one that allocated it is therefore credited to the
+//! freeing thread, which can make a phase look like it freed more than it allocated.
+
+use std::fmt;
+
+/// What one phase allocated.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of allocations, counting each reallocation as one.
+    pub allocations: u64,
+    /// Total bytes requested, including the full new size of every reallocation.
+    pub bytes: u64,
+    /// Most bytes live at once, over what was already live when the phase started.
+    pub peak: u64,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} bytes, peak {} bytes",
+            self.allocations, self.bytes, self.peak
+        )
+    }
+}
+
+/// Runs `f` and returns what it allocated on this thread, or `None` when allocations are not
+/// being counted.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    #[cfg(feature = "count-allocations")]
+    {
+        let (result, stats) = counting::measure(f);
+        (result, Some(stats))
+    }
+    #[cfg(not(feature = "count-allocations"))]
+    {
+        (f(), None)
+    }
+}
+
+#[cfg(feature = "count-allocations")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    use super::AllocStats;
+
+    struct Counters {
+        allocations: Cell<u64>,
+        bytes: Cell<u64>,
+        live: Cell<i64>,
+        peak: Cell<i64>,
+    }
+
+    thread_local! {
+        // Const-initialised and without a destructor, so it never allocates and stays usable
+        // while the thread shuts down.
+        static COUNTERS: Counters = const {
+            Counters {
+                allocations: Cell::new(0),
+                bytes: Cell::new(0),
+                live: Cell::new(0),
+                peak: Cell::new(0),
+            }
+        };
+    }
+
+    fn record(allocated: usize, freed: usize) {
+        let _ = COUNTERS.try_with(|counters| {
+            if allocated > 0 {
+                counters.allocations.set(counters.allocations.get() + 1);
+                counters.bytes.set(counters.bytes.get() + allocated as u64);
+            }
+            let live = counters.live.get() + allocated as i64 - freed as i64;
+            counters.live.set(live);
+            counters.peak.set(counters.peak.get().max(live));
+        });
+    }
+
+    struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let pointer = unsafe { System.alloc(layout) };
+            if !pointer.is_null() {
+                record(layout.size(), 0);
+            }
+            pointer
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let pointer = unsafe { System.alloc_zeroed(layout) };
+            if !pointer.is_null() {
+                record(layout.size(), 0);
+            }
+            pointer
+        }
+
+        unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(pointer, layout) };
+            record(0, layout.size());
+        }
+
+        unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_pointer = unsafe { System.realloc(pointer, layout, new_size) };
+            if !new_pointer.is_null() {
+                record(new_size, layout.size());
+            }
+            new_pointer
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    pub(super) fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+        let (allocations, bytes, live, outer_peak) = COUNTERS.with(|counters| {
+            let snapshot = (
+                counters.allocations.get(),
+                counters.bytes.get(),
+                counters.live.get(),
+                counters.peak.get(),
+            );
+            counters.peak.set(snapshot.2);
+            snapshot
+        });
+
+        let result = f();
+
+        let stats = COUNTERS.with(|counters| {
+            let peak = counters.peak.get();
+            // Keep the peak of any measurement this one is nested in.
+            counters.peak.set(outer_peak.max(peak));
+            AllocStats {
+                allocations: counters.allocations.get() - allocations,
+                bytes: counters.bytes.get() - bytes,
+                peak: (peak - live).max(0) as u64,
+            }
+        });
+        (result, stats)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn counts_allocations_bytes_and_peak() {
+            let ((), stats) = measure(|| {
+                let first = vec![0_u8; 1000];
+                drop(first);
+                let mut second = Vec::<u8>::with_capacity(100);
+                second.reserve_exact(300);
+            });
+
+            assert_eq!(stats.allocations, 3);
+            assert_eq!(stats.bytes, 1400);
+            assert_eq!(stats.peak, 1000);
+        }
+    }
+}
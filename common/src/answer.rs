@@ -0,0 +1,210 @@
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// A part's answer, kept structured so that answers from different sources compare by meaning
+/// rather than by exact text.
+///
+/// Solutions return any type that converts into an `Answer`: integers become
+/// [`Answer::Integer`], strings [`Answer::Text`], and days that draw their answer, like 2022
+/// day 10's CRT, build an [`Answer::Grid`]. Answers read back from text, such as a file of
+/// accepted answers, go through [`FromStr`], which picks the variant from the shape of the
+/// text.
+///
+/// A part that cannot solve an input its parse accepted, e.g. one too short for part 2, returns
+/// a `Result` whose error becomes an [`Answer::Error`], which runners report as a failure. A
+/// part that is not written yet, like those of a freshly scaffolded day, returns
+/// [`Answer::Unsolved`], which runners report without failing.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    /// Rows of a picture, compared without trailing whitespace or trailing blank rows.
+    Grid(Vec<String>),
+    /// Why the part has no answer for this input.
+    Error(String),
+    /// The part is not solved yet.
+    Unsolved,
+}
+
+impl Answer {
+    /// Splits `text` into the rows of a grid.
+    pub fn grid(text: &str) -> Self {
+        Answer::Grid(text.lines().map(str::to_string).collect())
+    }
+
+    /// The message of an [`Answer::Error`].
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            Answer::Error(message) => Some(message),
+            _ => None,
+        }
+    }
+
+    pub fn is_unsolved(&self) -> bool {
+        matches!(self, Answer::Unsolved)
+    }
+
+    pub fn is_multi_line(&self) -> bool {
+        match self {
+            Answer::Integer(_) | Answer::Error(_) | Answer::Unsolved => false,
+            Answer::Text(text) => text.contains('\n'),
+            Answer::Grid(rows) => rows.len() > 1,
+        }
+    }
+
+    /// Reads text answers the way [`FromStr`] would, so that `Text("42")` meets `Integer(42)`.
+    fn normalized(&self) -> Cow<'_, Answer> {
+        match self {
+            Answer::Text(text) => {
+                let Ok(answer) = text.parse();
+                Cow::Owned(answer)
+            }
+            _ => Cow::Borrowed(self),
+        }
+    }
+}
+
+fn trimmed_rows(rows: &[String]) -> impl Iterator<Item = &str> {
+    let len = rows
+        .iter()
+        .rposition(|row| !row.trim_end().is_empty())
+        .map_or(0, |last| last + 1);
+    rows[..len].iter().map(|row| row.trim_end())
+}
+
+/// Neither an [`Answer::Error`] nor [`Answer::Unsolved`] is an answer, so they equal nothing,
+/// not even themselves; compare [`Answer::error`] to match an error's message.
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (&*self.normalized(), &*other.normalized()) {
+            (Answer::Integer(left), Answer::Integer(right)) => left == right,
+            (Answer::Text(left), Answer::Text(right)) => left == right,
+            (Answer::Grid(left), Answer::Grid(right)) => trimmed_rows(left).eq(trimmed_rows(right)),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(number) => write!(f, "{number}"),
+            Answer::Text(text) => f.write_str(text),
+            Answer::Grid(rows) => f.write_str(&rows.join("\n")),
+            Answer::Error(message) => write!(f, "error: {message}"),
+            Answer::Unsolved => f.write_str("unsolved"),
+        }
+    }
+}
+
+/// Integers become [`Answer::Integer`], text spanning several lines [`Answer::Grid`], and
+/// anything else [`Answer::Text`].
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(if let Ok(number) = text.trim().parse() {
+            Answer::Integer(number)
+        } else if text.contains('\n') {
+            Answer::grid(text)
+        } else {
+            Answer::Text(text.to_string())
+        })
+    }
+}
+
+macro_rules! from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Answer {
+                fn from(number: $integer) -> Self {
+                    Answer::Integer(number.into())
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+impl From<isize> for Answer {
+    fn from(number: isize) -> Self {
+        Answer::Integer(number as i128)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(number: usize) -> Self {
+        Answer::Integer(number as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+impl<T, E> From<Result<T, E>> for Answer
+where
+    T: Into<Answer>,
+    E: fmt::Display,
+{
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(answer) => answer.into(),
+            Err(error) => Answer::Error(error.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grids_ignore_trailing_whitespace() {
+        let drawn = Answer::Grid(vec!["#..#  ".into(), ".##.".into(), "".into()]);
+
+        assert_eq!(drawn, Answer::grid("#..#\n.##.\n"));
+        assert_eq!(drawn, "#..#\n.##.".parse().unwrap());
+        assert_ne!(drawn, Answer::grid("#..#\n.#..\n"));
+        assert_eq!(drawn.to_string(), "#..#  \n.##.\n");
+    }
+
+    #[test]
+    fn compares_text_by_meaning() {
+        assert_eq!(Answer::from(24000_u32), Answer::from("24000"));
+        assert_eq!(Answer::from(-3_i64), "-3".parse().unwrap());
+        assert_eq!(Answer::from("CMZ"), "CMZ".parse().unwrap());
+        assert_ne!(Answer::from("CMZ"), Answer::from("MCD"));
+        assert_ne!(Answer::from(1_usize), Answer::from("CMZ"));
+    }
+
+    #[test]
+    fn failed_parts_never_match_an_answer() {
+        let failed = Answer::from(Err::<u32, _>("no marker"));
+
+        assert_eq!(failed.error(), Some("no marker"));
+        assert_eq!(Answer::from(Ok::<u32, String>(7)), Answer::from(7_u32));
+        assert_ne!(failed, "error: no marker".parse().unwrap());
+        assert_ne!(failed, failed.clone());
+        assert_eq!(failed.to_string(), "error: no marker");
+    }
+
+    #[test]
+    fn unsolved_parts_never_match_an_answer() {
+        assert_ne!(Answer::Unsolved, Answer::Unsolved);
+        assert_ne!(Answer::Unsolved, "unsolved".parse().unwrap());
+        assert!(Answer::Unsolved.is_unsolved());
+        assert_eq!(Answer::Unsolved.to_string(), "unsolved");
+    }
+}
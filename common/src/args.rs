@@ -0,0 +1,160 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::Part;
+
+pub(crate) const USAGE: &str =
+    "usage: <day> [--part 1|2] [--strict] [-v...] [--<param> <value>]... <input path, or - for stdin>";
+
+/// Command line of a per-day binary. Every `--name value` pair other than `--part` is handed to
+/// [`Solution::set_param`](crate::Solution::set_param), so days decide which names they accept.
+/// `--strict` and `-v`, repeated for more detail, are the flags that take no value.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Args {
+    pub(crate) input: String,
+    pub(crate) part: Option<Part>,
+    pub(crate) strict: bool,
+    pub(crate) verbosity: u8,
+    pub(crate) params: Vec<(String, String)>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum UsageError {
+    HelpRequested,
+    MissingValue(String),
+    InvalidPart(String),
+    MissingInput,
+    UnexpectedArgument(String),
+}
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UsageError::HelpRequested => write!(f, "help requested"),
+            UsageError::MissingValue(name) => write!(f, "missing value for `--{name}`"),
+            UsageError::InvalidPart(value) => write!(f, "invalid part `{value}`, expected 1 or 2"),
+            UsageError::MissingInput => write!(f, "missing input path"),
+            UsageError::UnexpectedArgument(argument) => {
+                write!(f, "unexpected argument `{argument}`")
+            }
+        }
+    }
+}
+
+impl Error for UsageError {}
+
+impl Args {
+    /// Parses the arguments after the program name. Values can follow their flag either as the
+    /// next argument or after `=`.
+    pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, UsageError> {
+        let mut parsed = Args::default();
+        let mut input = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                return Err(UsageError::HelpRequested);
+            }
+            if let Some(vs) = arg.strip_prefix('-').filter(|vs| is_verbosity(vs)) {
+                parsed.verbosity = parsed.verbosity.saturating_add(vs.len() as u8);
+                continue;
+            }
+            let Some(flag) = arg.strip_prefix("--") else {
+                if input.replace(arg.clone()).is_some() {
+                    return Err(UsageError::UnexpectedArgument(arg));
+                }
+                continue;
+            };
+            if flag == "strict" {
+                parsed.strict = true;
+                continue;
+            }
+            if flag == "verbose" {
+                parsed.verbosity = parsed.verbosity.saturating_add(1);
+                continue;
+            }
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| UsageError::MissingValue(flag.to_string()))?;
+                    (flag.to_string(), value)
+                }
+            };
+            if name == "part" {
+                let part = value.parse().ok().and_then(Part::from_number);
+                parsed.part = Some(part.ok_or(UsageError::InvalidPart(value))?);
+            } else {
+                parsed.params.push((name, value));
+            }
+        }
+        parsed.input = input.ok_or(UsageError::MissingInput)?;
+        Ok(parsed)
+    }
+
+    pub(crate) fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
+}
+
+/// Whether `flags` is the part after the dash of `-v`, `-vv` and so on.
+fn is_verbosity(flags: &str) -> bool {
+    !flags.is_empty() && flags.bytes().all(|flag| flag == b'v')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, UsageError> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn collects_part_params_and_input() {
+        let args = parse(&[
+            "--part",
+            "2",
+            "--row=10",
+            "--strict",
+            "-vv",
+            "-",
+            "--verbose",
+            "--max-coord",
+            "20",
+        ])
+        .unwrap();
+
+        assert_eq!(args.input, "-");
+        assert_eq!(args.parts(), vec![Part::Two]);
+        assert!(args.strict);
+        assert_eq!(args.verbosity, 3);
+        assert_eq!(
+            args.params,
+            vec![
+                ("row".to_string(), "10".to_string()),
+                ("max-coord".to_string(), "20".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_command_lines() {
+        assert_eq!(
+            parse(&["--part", "3", "input.txt"]),
+            Err(UsageError::InvalidPart("3".into()))
+        );
+        assert_eq!(
+            parse(&["input.txt", "--row"]),
+            Err(UsageError::MissingValue("row".into()))
+        );
+        assert_eq!(parse(&["--part", "1"]), Err(UsageError::MissingInput));
+        assert_eq!(
+            parse(&["a.txt", "b.txt"]),
+            Err(UsageError::UnexpectedArgument("b.txt".into()))
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Returned by [`Solution::parse`](crate::Solution::parse) when the input does not match the
+/// puzzle format, pointing at the first place it went wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    snippet: String,
+    expected: String,
+}
+
+impl ParseError {
+    /// Builds an error at the start of `position`, which has to be a slice of `input` (an empty
+    /// slice at its end points past the last character).
+    pub fn at(input: &str, position: &str, expected: impl Into<String>) -> Self {
+        let offset = (position.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+        debug_assert!(
+            offset <= input.len(),
+            "position should be a slice of the input"
+        );
+        Self::at_offset(input, offset.min(input.len()), expected)
+    }
+
+    /// Builds an error at byte `offset` of `input`.
+    pub fn at_offset(input: &str, offset: usize, expected: impl Into<String>) -> Self {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line_start = input[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+        Self {
+            line: input[..line_start].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            snippet: input[line_start..]
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// One-based line of the offending input.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// One-based column, counted in characters, of the offending input.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The whole line the error points into.
+    pub fn snippet(&self) -> &str {
+        &self.snippet
+    }
+
+    /// What the parser was looking for, e.g. `a number` or `" -> "`.
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "failed to parse input at line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        writeln!(f, "    {}", self.snippet)?;
+        write!(f, "    {:>width$}", "^", width = self.column)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Returned by [`Solution::set_param`](crate::Solution::set_param) when a named puzzle
+/// parameter does not exist for the day or its value cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    Unknown { name: String },
+    Invalid { name: String, value: String },
+}
+
+impl ParamError {
+    pub fn unknown(name: &str) -> Self {
+        ParamError::Unknown {
+            name: name.to_string(),
+        }
+    }
+
+    pub fn invalid(name: &str, value: &str) -> Self {
+        ParamError::Invalid {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// Parses `value` for the parameter `name`, reporting it as invalid when it does not parse.
+    pub fn parse_value<T: FromStr>(name: &str, value: &str) -> Result<T, Self> {
+        value.parse().map_err(|_| ParamError::invalid(name, value))
+    }
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Unknown { name } => write!(f, "unknown parameter `{name}`"),
+            ParamError::Invalid { name, value } => {
+                write!(f, "invalid value `{value}` for parameter `{name}`")
+            }
+        }
+    }
+}
+
+impl Error for ParamError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_at_line_and_column() {
+        let input = "1000\n2000\n30x0\n";
+        let error = ParseError::at(input, &input[12..], "a number");
+
+        assert_eq!((error.line(), error.column()), (3, 3));
+        assert_eq!(error.snippet(), "30x0");
+        assert_eq!(
+            error.to_string(),
+            "failed to parse input at line 3, column 3: expected a number\n    30x0\n      ^"
+        );
+    }
+
+    #[test]
+    fn points_past_the_end() {
+        let input = "move 1 from 2";
+        let error = ParseError::at(input, &input[input.len()..], "\" to \"");
+
+        assert_eq!((error.line(), error.column()), (1, 14));
+        assert_eq!(error.snippet(), "move 1 from 2");
+    }
+}
@@ -0,0 +1,238 @@
+//! Tests for the puzzle examples in each day's `assets/` directory.
+//!
+//! Every day keeps its examples as `assets/example*.txt` next to `input.txt`, plus an
+//! `assets/examples.toml` manifest listing what each example should produce:
+//!
+//! ```toml
+//! [[example]]
+//! file = "example.txt"
+//! part1 = 26
+//! part2 = 56000011
+//! params = { row = 10, max-coord = 20 }
+//! ```
+//!
+//! Either answer may be left out for examples that only apply to one part, and `params` are
+//! passed to [`Solution::set_param`] before solving. The day's `lib.rs` then names each
+//! example and the parts it applies to with [`example_tests!`](crate::example_tests), which
+//! produces one `#[test]` per example and part, e.g. `examples::example_2::part_2`, that
+//! checks the answer the manifest gives, and a test that fails if the manifest gives answers
+//! that no test checks.
+
+use crate::{Answer, Part, Solution};
+
+/// Solves `part` of `input`, normalized as puzzle inputs are, after applying `params`, and
+/// panics unless the answer equals `expected` read as an [`Answer`].
+pub fn check<S>(input: &str, part: Part, params: &[(&str, &str)], expected: &str)
+where
+    S: Solution + Default,
+{
+    let mut solution = S::default();
+    for (name, value) in params {
+        if let Err(error) = solution.set_param(name, value) {
+            panic!("{error}");
+        }
+    }
+
+    let (input, _) = crate::input::normalize(input);
+    let answer: Result<Answer, _> = match part {
+        Part::One => solution.solve_part_1(&input).map(Into::into),
+        Part::Two => solution.solve_part_2(&input).map(Into::into),
+    };
+    let Ok(expected) = expected.parse::<Answer>();
+    match answer {
+        Ok(Answer::Error(error)) => panic!("{error}"),
+        Ok(Answer::Unsolved) => panic!("part {part} is not solved yet"),
+        Ok(answer) => assert_eq!(answer, expected),
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Looks up `file` in the text of an `assets/examples.toml` manifest and [`check`]s `part` of
+/// `input` against the answer and params listed there. This is what the tests from
+/// [`example_tests!`](crate::example_tests) call.
+///
+/// # Panics
+///
+/// Panics if the manifest is malformed or gives no answer for `part` of `file`, so that an
+/// example named in the macro cannot pass without being checked.
+#[cfg(feature = "examples")]
+pub fn check_example<S>(manifest: &str, file: &str, input: &str, part: Part)
+where
+    S: Solution + Default,
+{
+    let examples = parse_manifest(manifest).unwrap_or_else(|error| panic!("{error}"));
+    let Some(example) = examples.iter().find(|example| example.file == file) else {
+        panic!("assets/examples.toml lists no {file}");
+    };
+    let Some((_, expected)) = example.expected.iter().find(|(of, _)| *of == part) else {
+        panic!("assets/examples.toml gives no part {part} answer for {file}");
+    };
+    let params: Vec<_> = example
+        .params
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    check::<S>(input, part, &params, expected);
+}
+
+/// Panics unless every example and part with an answer in the text of an
+/// `assets/examples.toml` manifest is among `covered`, the files and parts given to
+/// [`example_tests!`](crate::example_tests), so that no listed answer goes unchecked.
+#[cfg(feature = "examples")]
+pub fn check_coverage(manifest: &str, covered: &[(&str, &[Part])]) {
+    let examples = parse_manifest(manifest).unwrap_or_else(|error| panic!("{error}"));
+    let unchecked: Vec<_> = examples
+        .iter()
+        .flat_map(|example| {
+            let parts = covered
+                .iter()
+                .find(|(file, _)| *file == example.file)
+                .map_or(&[][..], |(_, parts)| parts);
+            example
+                .expected
+                .iter()
+                .filter(move |(part, _)| !parts.contains(part))
+                .map(move |(part, _)| format!("part {part} of {}", example.file))
+        })
+        .collect();
+    assert!(
+        unchecked.is_empty(),
+        "assets/examples.toml gives answers that example_tests! does not check: {}",
+        unchecked.join(", ")
+    );
+}
+
+/// Declares one test per example and part for the given solution type, checking each part of
+/// `assets/<example>.txt` against the calling crate's `assets/examples.toml`, plus
+/// `examples::covers_the_manifest`, which fails if the manifest has answers no test checks.
+/// Attributes before a part, such as `#[ignore]`, go on its test:
+///
+/// ```ignore
+/// common::example_tests!(Day09, example: [part_1, part_2], example_2: [part_2]);
+/// common::example_tests!(Day16, example: [part_1, #[ignore = "not solved yet"] part_2]);
+/// ```
+///
+/// Needs the `examples` feature of `common`, which days enable for their tests only.
+#[macro_export]
+macro_rules! example_tests {
+    ($solution:ty, $($example:ident: [$($(#[$attr:meta])* $part:ident),+ $(,)?]),+ $(,)?) => {
+        #[cfg(test)]
+        mod examples {
+            #[test]
+            fn covers_the_manifest() {
+                $crate::examples::check_coverage(
+                    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/examples.toml")),
+                    &[$((
+                        concat!(stringify!($example), ".txt"),
+                        &[$($crate::example_tests!(@part $part)),+],
+                    )),+],
+                );
+            }
+
+            $(
+                mod $example {
+                    #[allow(unused_imports)]
+                    use super::super::*;
+
+                    $(
+                        #[test]
+                        $(#[$attr])*
+                        fn $part() {
+                            $crate::examples::check_example::<$solution>(
+                                include_str!(concat!(
+                                    env!("CARGO_MANIFEST_DIR"),
+                                    "/assets/examples.toml"
+                                )),
+                                concat!(stringify!($example), ".txt"),
+                                include_str!(concat!(
+                                    env!("CARGO_MANIFEST_DIR"),
+                                    "/assets/",
+                                    stringify!($example),
+                                    ".txt"
+                                )),
+                                $crate::example_tests!(@part $part),
+                            );
+                        }
+                    )+
+                }
+            )+
+        }
+    };
+    (@part part_1) => {
+        $crate::Part::One
+    };
+    (@part part_2) => {
+        $crate::Part::Two
+    };
+}
+
+/// One entry of an `assets/examples.toml` manifest.
+#[cfg(feature = "examples")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// The example's file name within `assets/`.
+    pub file: String,
+    pub params: Vec<(String, String)>,
+    /// The expected answer of each part the example applies to.
+    pub expected: Vec<(Part, String)>,
+}
+
+/// Reads the examples listed in `assets/examples.toml` under `assets`, describing the problem
+/// if the manifest is missing or malformed.
+#[cfg(feature = "examples")]
+pub fn read_manifest(assets: &std::path::Path) -> Result<Vec<Example>, String> {
+    let manifest_path = assets.join("examples.toml");
+    std::fs::read_to_string(&manifest_path)
+        .map_err(|error| error.to_string())
+        .and_then(|manifest| parse_manifest(&manifest))
+        .map_err(|error| format!("{}: {error}", manifest_path.display()))
+}
+
+/// Reads the examples listed in the text of an `assets/examples.toml` manifest.
+#[cfg(feature = "examples")]
+pub fn parse_manifest(manifest: &str) -> Result<Vec<Example>, String> {
+    let manifest = manifest
+        .parse::<toml::Table>()
+        .map_err(|error| error.to_string())?;
+
+    let as_string = |value: &toml::Value, what: &str| match value {
+        toml::Value::String(value) => Ok(value.clone()),
+        toml::Value::Integer(value) => Ok(value.to_string()),
+        _ => Err(format!("{what} should be a string or an integer")),
+    };
+
+    let examples = match manifest.get("example") {
+        Some(toml::Value::Array(examples)) => examples.as_slice(),
+        Some(_) => return Err("`example` should be an array of tables".into()),
+        None => &[],
+    };
+    examples
+        .iter()
+        .map(|example| {
+            let file = example
+                .get("file")
+                .and_then(toml::Value::as_str)
+                .ok_or("example without a `file`")?;
+            let params = match example.get("params") {
+                Some(toml::Value::Table(params)) => params
+                    .iter()
+                    .map(|(name, value)| Ok((name.clone(), as_string(value, name)?)))
+                    .collect::<Result<_, String>>()?,
+                Some(_) => return Err(format!("`params` of {file} should be a table")),
+                None => Vec::new(),
+            };
+            let expected = Part::ALL
+                .into_iter()
+                .filter_map(|part| {
+                    let expected = example.get(format!("part{part}"))?;
+                    Some(as_string(expected, &format!("part{part} of {file}")).map(|e| (part, e)))
+                })
+                .collect::<Result<_, String>>()?;
+            Ok(Example {
+                file: file.to_string(),
+                params,
+                expected,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,208 @@
+//! Loading puzzle inputs before they reach a parser.
+//!
+//! Parsers are written against inputs as the site serves them: `\n` line endings, no byte order
+//! mark and exactly one newline after the last line. A file saved on Windows or through an
+//! editor can differ from that in ways that are invisible but break parsing, so inputs are
+//! normalized on the way in. In strict mode such an input is refused instead, with a list of
+//! what normalizing it would have changed.
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// One way an input differed from the form the parsers expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// A UTF-8 byte order mark was removed from the start.
+    ByteOrderMark,
+    /// This many `\r\n` line endings were replaced with `\n`.
+    CrLf(usize),
+    /// The last line did not end in a newline, so one was added.
+    MissingFinalNewline,
+    /// This many blank lines after the last line were removed.
+    ExtraFinalNewlines(usize),
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::ByteOrderMark => write!(f, "removed a UTF-8 byte order mark"),
+            Change::CrLf(1) => write!(f, "replaced 1 CRLF line ending with LF"),
+            Change::CrLf(count) => write!(f, "replaced {count} CRLF line endings with LF"),
+            Change::MissingFinalNewline => write!(f, "added a newline after the last line"),
+            Change::ExtraFinalNewlines(1) => write!(f, "removed 1 blank line at the end"),
+            Change::ExtraFinalNewlines(count) => {
+                write!(f, "removed {count} blank lines at the end")
+            }
+        }
+    }
+}
+
+/// Why an input could not be loaded.
+#[derive(Debug)]
+pub enum InputError {
+    Io(io::Error),
+    /// Strict mode found an input that would have needed these changes.
+    NotNormalized(Vec<Change>),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io(error) => write!(f, "{error}"),
+            InputError::NotNormalized(changes) => {
+                let changes: Vec<String> = changes.iter().map(Change::to_string).collect();
+                write!(
+                    f,
+                    "input is not normalized, loading it would have {}",
+                    changes.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InputError::Io(error) => Some(error),
+            InputError::NotNormalized(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for InputError {
+    fn from(error: io::Error) -> Self {
+        InputError::Io(error)
+    }
+}
+
+/// Strips a byte order mark, turns CRLF line endings into LF and leaves exactly one newline
+/// after the last line, returning the result along with what was changed. Whitespace within
+/// lines is kept, since some puzzles draw with it. An input with no lines stays empty.
+pub fn normalize(raw: &str) -> (Cow<'_, str>, Vec<Change>) {
+    let mut changes = Vec::new();
+    let mut text = Cow::Borrowed(raw);
+
+    if let Some(rest) = raw.strip_prefix(BYTE_ORDER_MARK) {
+        changes.push(Change::ByteOrderMark);
+        text = Cow::Borrowed(rest);
+    }
+
+    let crlf_count = text.matches("\r\n").count();
+    if crlf_count > 0 {
+        changes.push(Change::CrLf(crlf_count));
+        text = Cow::Owned(text.replace("\r\n", "\n"));
+    }
+
+    let body_length = text.trim_end_matches('\n').len();
+    let newlines = text.len() - body_length;
+    match (body_length, newlines) {
+        (0, 0) | (1.., 1) => {}
+        (0, blank) => {
+            changes.push(Change::ExtraFinalNewlines(blank));
+            text = Cow::Borrowed("");
+        }
+        (_, 0) => {
+            changes.push(Change::MissingFinalNewline);
+            text.to_mut().push('\n');
+        }
+        (_, newlines) => {
+            changes.push(Change::ExtraFinalNewlines(newlines - 1));
+            match &mut text {
+                Cow::Borrowed(borrowed) => *borrowed = &borrowed[..=body_length],
+                Cow::Owned(owned) => owned.truncate(body_length + 1),
+            }
+        }
+    }
+
+    (text, changes)
+}
+
+/// Normalizes `raw` with [`normalize`], or in `strict` mode refuses it if that changed anything.
+pub fn prepare(raw: String, strict: bool) -> Result<String, InputError> {
+    let (text, changes) = normalize(&raw);
+    if changes.is_empty() {
+        Ok(raw)
+    } else if strict {
+        Err(InputError::NotNormalized(changes))
+    } else {
+        Ok(text.into_owned())
+    }
+}
+
+/// Reads the input at `path` and [`prepare`]s it.
+pub fn read(path: impl AsRef<Path>, strict: bool) -> Result<String, InputError> {
+    prepare(fs::read_to_string(path)?, strict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_well_formed_input_alone() {
+        for raw in ["", "1000\n\n2000\n", "    [D]    \n[N] [C]    \n"] {
+            let (text, changes) = normalize(raw);
+            assert!(matches!(text, Cow::Borrowed(_)));
+            assert_eq!(text, raw);
+            assert_eq!(changes, []);
+        }
+    }
+
+    #[test]
+    fn normalizes_windows_input() {
+        let (text, changes) = normalize("\u{feff}1000\r\n\r\n2000\r\n");
+
+        assert_eq!(text, "1000\n\n2000\n");
+        assert_eq!(changes, [Change::ByteOrderMark, Change::CrLf(3)]);
+    }
+
+    #[test]
+    fn leaves_exactly_one_final_newline() {
+        assert_eq!(
+            normalize("1000\n2000"),
+            (
+                Cow::Borrowed("1000\n2000\n"),
+                vec![Change::MissingFinalNewline]
+            )
+        );
+        assert_eq!(
+            normalize("1000\n2000\n\n\n"),
+            (
+                Cow::Borrowed("1000\n2000\n"),
+                vec![Change::ExtraFinalNewlines(2)]
+            )
+        );
+        assert_eq!(
+            normalize("\r\n\n"),
+            (
+                Cow::Borrowed(""),
+                vec![Change::CrLf(1), Change::ExtraFinalNewlines(2)]
+            )
+        );
+    }
+
+    #[test]
+    fn strict_mode_reports_changes_instead_of_making_them() {
+        assert_eq!(prepare("1\r\n2".into(), false).unwrap(), "1\n2\n");
+
+        let error = prepare("1\r\n2".into(), true).unwrap_err();
+        assert!(matches!(
+            &error,
+            InputError::NotNormalized(changes)
+                if changes == &[Change::CrLf(1), Change::MissingFinalNewline]
+        ));
+        assert_eq!(
+            error.to_string(),
+            "input is not normalized, loading it would have replaced 1 CRLF line ending with \
+             LF, added a newline after the last line"
+        );
+        assert_eq!(prepare("1\n2\n".into(), true).unwrap(), "1\n2\n");
+    }
+}
@@ -0,0 +1,100 @@
+pub mod alloc;
+mod answer;
+mod args;
+mod error;
+pub mod examples;
+pub mod input;
+#[cfg(feature = "log")]
+pub mod log;
+#[cfg(feature = "nom")]
+pub mod nom;
+pub mod parse;
+mod solution;
+#[cfg(feature = "visualize")]
+pub mod visual;
+
+use std::env;
+use std::io::{self, Read};
+use std::process;
+
+use args::{Args, UsageError, USAGE};
+
+pub use answer::Answer;
+pub use error::{ParamError, ParseError};
+pub use solution::{DynSolution, Part, PartResult, Solution, Solved};
+
+/// Shared `main` for the per-day binaries: reads the input from the path given on the command
+/// line, or from stdin when it is `-`, and prints the answers. `--part` limits the run to one
+/// part, `--strict` refuses inputs that [`input::normalize`] would change, `-v` reports progress
+/// on stderr through `common::log` when the `log` feature is on, and any other `--name value`
+/// pair sets a puzzle parameter through [`Solution::set_param`].
+pub fn main<S>()
+where
+    S: Solution + Default + Send + Sync,
+{
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(UsageError::HelpRequested) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(error) => {
+            eprintln!("{error}\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    #[cfg(feature = "log")]
+    log::init(args.verbosity);
+
+    let mut solution = S::default();
+    for (name, value) in &args.params {
+        if let Err(error) = solution.set_param(name, value) {
+            eprintln!("{error}");
+            process::exit(2);
+        }
+    }
+
+    let input = if args.input == "-" {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(input::InputError::from)
+            .and_then(|_| input::prepare(input, args.strict))
+    } else {
+        input::read(&args.input, args.strict)
+    };
+    let input = input.unwrap_or_else(|error| {
+        eprintln!("failed to read input from {}: {error}", args.input);
+        process::exit(1);
+    });
+
+    match solution.run(&input, &args.parts()) {
+        Ok(solved) => {
+            for result in &solved.parts {
+                if let Some(error) = result.answer.error() {
+                    eprintln!("part {}: {error}", result.part);
+                    process::exit(1);
+                }
+                println!("{}", result.answer);
+            }
+            print_alloc_stats(&solved);
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Reports heap use on stderr, keeping stdout to the answers, when allocations are counted.
+fn print_alloc_stats(solved: &Solved) {
+    if let Some(stats) = solved.parse_alloc {
+        eprintln!("parse: {stats}");
+    }
+    for result in &solved.parts {
+        if let Some(stats) = result.alloc {
+            eprintln!("part {}: {stats}", result.part);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Diagnostics for the runners. Solutions report what they are doing with [`tracing`] spans and
+//! events, and [`init`] prints them on stderr so that stdout carries nothing but answers.
+//!
+//! Each phase of a run gets a span, `parse` and `part`, which is reported with its timings when
+//! it closes at the `info` level. Days add progress events below that: summaries at `debug`, and
+//! per-step events such as day 11's rounds at `trace`.
+
+use std::io::{self, IsTerminal};
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+/// The most detailed level to report for `verbosity` repetitions of `-v`: warnings alone by
+/// default, then `info`, `debug` and `trace`.
+pub fn level(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Sends spans and events to stderr. An explicit `-v` decides the level, otherwise `RUST_LOG`
+/// does when it is set, which also allows filtering by day, e.g.
+/// `RUST_LOG=aoc_2022_day_17=trace`. Does nothing if a subscriber is already installed.
+pub fn init(verbosity: u8) {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) if verbosity == 0 => filter,
+        _ => EnvFilter::default().add_directive(level(verbosity).into()),
+    };
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_ansi(io::stderr().is_terminal())
+        .with_writer(io::stderr)
+        .try_init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_v_reports_one_more_level() {
+        let levels: Vec<LevelFilter> = (0..5).map(level).collect();
+        assert_eq!(
+            levels,
+            [
+                LevelFilter::WARN,
+                LevelFilter::INFO,
+                LevelFilter::DEBUG,
+                LevelFilter::TRACE,
+                LevelFilter::TRACE,
+            ]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Glue for days that parse their input with `nom`.
+//!
+//! Parsers use [`IResult`] so that failures carry an [`Error`] which remembers where it
+//! happened and what was expected there, and [`finish`] turns the result into a
+//! [`ParseError`]. Prefer [`tag`] over `nom`'s own so that the error names the literal, and
+//! [`separated_to_end`] over `separated_list1` for the top-level list, which would otherwise stop
+//! quietly at the first malformed entry and drop the rest of the input.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use nom::error::{ContextError, ErrorKind, FromExternalError};
+
+use crate::ParseError;
+
+pub type IResult<'a, O> = nom::IResult<&'a str, O, Error<'a>>;
+
+/// A `nom` error that keeps the remaining input at the point of failure together with a
+/// description of what was expected there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error<'a> {
+    position: &'a str,
+    expected: Cow<'static, str>,
+}
+
+impl<'a> Error<'a> {
+    pub fn new(position: &'a str, expected: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            position,
+            expected: expected.into(),
+        }
+    }
+}
+
+fn describe(kind: ErrorKind) -> Cow<'static, str> {
+    match kind {
+        ErrorKind::Digit => "a number".into(),
+        ErrorKind::Alpha => "a letter".into(),
+        ErrorKind::AlphaNumeric => "a letter or digit".into(),
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace".into(),
+        ErrorKind::CrLf => "a line break".into(),
+        ErrorKind::Eof => "end of input".into(),
+        kind => kind.description().to_lowercase().into(),
+    }
+}
+
+impl<'a> nom::error::ParseError<&'a str> for Error<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        Self::new(input, describe(kind))
+    }
+
+    fn append(_input: &'a str, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: &'a str, expected: char) -> Self {
+        Self::new(input, format!("{expected:?}"))
+    }
+
+    /// Keeps whichever alternative got further, listing both when they failed at the same spot.
+    fn or(self, other: Self) -> Self {
+        match self.position.len().cmp(&other.position.len()) {
+            Ordering::Less => self,
+            Ordering::Greater => other,
+            Ordering::Equal if self.expected == other.expected => self,
+            Ordering::Equal => Self::new(
+                self.position,
+                format!("{} or {}", self.expected, other.expected),
+            ),
+        }
+    }
+}
+
+impl<'a> ContextError<&'a str> for Error<'a> {
+    /// Names what was expected when the wrapped parser failed right at its start; deeper errors
+    /// are more precise and are kept as they are.
+    fn add_context(input: &'a str, context: &'static str, other: Self) -> Self {
+        if other.position.len() == input.len() {
+            Self::new(input, context)
+        } else {
+            other
+        }
+    }
+}
+
+impl<'a, E> FromExternalError<&'a str, E> for Error<'a> {
+    fn from_external_error(input: &'a str, kind: ErrorKind, _error: E) -> Self {
+        Self::new(input, describe(kind))
+    }
+}
+
+/// Matches `literal`, failing with an error that names it.
+pub fn tag<'a>(literal: &'static str) -> impl FnMut(&'a str) -> IResult<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(literal) {
+        Some(rest) => Ok((rest, &input[..literal.len()])),
+        None => Err(nom::Err::Error(Error::new(input, format!("{literal:?}")))),
+    }
+}
+
+/// Parses `item` repeatedly, separated by `separator`, until only whitespace is left. Unlike
+/// `separated_list1`, an entry that fails to parse is reported instead of ending the list.
+pub fn separated_to_end<'a, O>(
+    separator: &'static str,
+    mut item: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    move |mut input: &'a str| {
+        let mut items = Vec::new();
+        loop {
+            let (rest, output) = item(input)?;
+            items.push(output);
+            if rest.trim().is_empty() {
+                return Ok((rest, items));
+            }
+            input = rest
+                .strip_prefix(separator)
+                .ok_or_else(|| nom::Err::Error(Error::new(rest, format!("{separator:?}"))))?;
+        }
+    }
+}
+
+/// Converts the result of parsing all of `input`, rejecting anything but whitespace left over.
+pub fn finish<'a, O>(input: &'a str, result: IResult<'a, O>) -> Result<O, ParseError> {
+    match result {
+        Ok((rest, output)) => {
+            crate::parse::end(input, rest)?;
+            Ok(output)
+        }
+        Err(nom::Err::Error(error) | nom::Err::Failure(error)) => {
+            Err(ParseError::at(input, error.position, error.expected))
+        }
+        Err(nom::Err::Incomplete(_)) => {
+            Err(ParseError::at(input, &input[input.len()..], "more input"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::character::complete;
+    use nom::sequence::preceded;
+
+    use super::*;
+
+    fn parse_moves(input: &str) -> Result<Vec<u32>, ParseError> {
+        finish(
+            input,
+            separated_to_end("\n", preceded(tag("move "), complete::u32))(input),
+        )
+    }
+
+    #[test]
+    fn reports_malformed_entries_instead_of_stopping() {
+        assert_eq!(parse_moves("move 1\nmove 2\n"), Ok(vec![1, 2]));
+
+        let error = parse_moves("move 1\nmove x\nmove 3\n").unwrap_err();
+        assert_eq!((error.line(), error.column()), (2, 6));
+        assert_eq!(error.expected(), "a number");
+
+        let error = parse_moves("move 1\nmvoe 2\n").unwrap_err();
+        assert_eq!((error.line(), error.column()), (2, 1));
+        assert_eq!(error.expected(), "\"move \"");
+    }
+
+    #[test]
+    fn rejects_trailing_input() {
+        let input = "move 1 2";
+        let error = finish(input, preceded(tag("move "), complete::u32)(input)).unwrap_err();
+        assert_eq!((error.line(), error.column()), (1, 8));
+        assert_eq!(error.expected(), "end of input");
+    }
+}
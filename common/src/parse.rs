@@ -0,0 +1,75 @@
+//! Helpers for days that parse their input by hand with `str` methods.
+//!
+//! Each helper takes the whole `input` alongside the slice it looks at, so that failures can
+//! report where in the input they happened. The slices have to come from `input` itself, e.g.
+//! through `lines`, `split` or `split_once`.
+
+use std::str::FromStr;
+
+use crate::ParseError;
+
+/// Parses `token`, a slice of `input`, as a number.
+pub fn number<T: FromStr>(input: &str, token: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::at(input, token, "a number"))
+}
+
+/// Splits `text`, a slice of `input`, around the first `delimiter`, failing at the end of
+/// `text` when there is none.
+pub fn split_once<'a>(
+    input: &str,
+    text: &'a str,
+    delimiter: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    text.split_once(delimiter)
+        .ok_or_else(|| ParseError::at(input, &text[text.len()..], format!("{delimiter:?}")))
+}
+
+/// Checks that every character of `text`, a slice of `input`, satisfies `valid`, failing at the
+/// first one that does not.
+pub fn all_chars(
+    input: &str,
+    text: &str,
+    expected: &str,
+    valid: impl Fn(char) -> bool,
+) -> Result<(), ParseError> {
+    match text.char_indices().find(|&(_, ch)| !valid(ch)) {
+        Some((index, _)) => Err(ParseError::at(input, &text[index..], expected)),
+        None => Ok(()),
+    }
+}
+
+/// Checks that nothing but whitespace is left in `rest`, the unparsed tail of `input`.
+pub fn end(input: &str, rest: &str) -> Result<(), ParseError> {
+    let rest = rest.trim_start();
+    if rest.is_empty() {
+        Ok(())
+    } else {
+        Err(ParseError::at(input, rest, "end of input"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_the_offending_token() {
+        let input = "498,4 -> 498,x";
+        let error = number::<u32>(input, &input[13..]).unwrap_err();
+        assert_eq!((error.line(), error.column()), (1, 14));
+        assert_eq!(error.expected(), "a number");
+
+        let error = split_once(input, &input[9..], " -> ").unwrap_err();
+        assert_eq!(error.column(), 15);
+        assert_eq!(error.expected(), "\" -> \"");
+    }
+
+    #[test]
+    fn rejects_trailing_input() {
+        let input = "noop\n\nextra\n";
+        assert!(end(input, &input[4..6]).is_ok());
+        assert_eq!(end(input, &input[4..]).unwrap_err().line(), 3);
+    }
+}
@@ -0,0 +1,155 @@
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use tracing::info_span;
+
+use crate::alloc::{self, AllocStats};
+use crate::{Answer, ParamError, ParseError};
+
+/// A single day's puzzle, split into a parse phase and two solve phases that share its output.
+///
+/// `Parsed` may borrow from the input, which lets days keep `&str` slices of it around instead
+/// of allocating owned copies.
+pub trait Solution {
+    type Parsed<'a>;
+    type Answer1: Into<Answer>;
+    type Answer2: Into<Answer>;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError>;
+
+    fn part_1(&self, parsed: &Self::Parsed<'_>) -> Self::Answer1;
+
+    fn part_2(&self, parsed: &Self::Parsed<'_>) -> Self::Answer2;
+
+    /// Sets a named puzzle parameter, such as the row that day 15 scans, which the examples use
+    /// with different values than the real input. Days without parameters reject every name.
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), ParamError> {
+        let _ = value;
+        Err(ParamError::unknown(name))
+    }
+
+    /// Parses `input` and solves part 1, mostly useful for tests against raw example text.
+    fn solve_part_1(&self, input: &str) -> Result<Self::Answer1, ParseError> {
+        Ok(self.part_1(&self.parse(input)?))
+    }
+
+    /// Parses `input` and solves part 2, mostly useful for tests against raw example text.
+    fn solve_part_2(&self, input: &str) -> Result<Self::Answer2, ParseError> {
+        Ok(self.part_2(&self.parse(input)?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    pub fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub part: Part,
+    pub answer: Answer,
+    pub time: Duration,
+    /// Heap use while solving, when built with the `count-allocations` feature.
+    pub alloc: Option<AllocStats>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Solved {
+    pub parse_time: Duration,
+    pub parse_alloc: Option<AllocStats>,
+    pub parts: Vec<PartResult>,
+}
+
+/// Object-safe view of a [`Solution`], so that days with different `Parsed` and answer types
+/// can sit side by side in the runner's registry.
+pub trait DynSolution: Send + Sync {
+    /// See [`Solution::set_param`].
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), ParamError>;
+
+    /// Parses `input` and solves `parts` in order, handing each result to `report` as soon as
+    /// it is ready so that callers can show progress or stop waiting on a slow part. Returns
+    /// the parse time and heap use.
+    fn run_with(
+        &self,
+        input: &str,
+        parts: &[Part],
+        report: &mut dyn FnMut(PartResult),
+    ) -> Result<(Duration, Option<AllocStats>), ParseError>;
+
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Solved, ParseError> {
+        let mut results = Vec::new();
+        let (parse_time, parse_alloc) =
+            self.run_with(input, parts, &mut |result| results.push(result))?;
+        Ok(Solved {
+            parse_time,
+            parse_alloc,
+            parts: results,
+        })
+    }
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Send + Sync,
+{
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), ParamError> {
+        Solution::set_param(self, name, value)
+    }
+
+    fn run_with(
+        &self,
+        input: &str,
+        parts: &[Part],
+        report: &mut dyn FnMut(PartResult),
+    ) -> Result<(Duration, Option<AllocStats>), ParseError> {
+        let start = Instant::now();
+        let (parsed, parse_alloc) = info_span!("parse", bytes = input.len())
+            .in_scope(|| alloc::measure(|| self.parse(input)));
+        let parse_time = start.elapsed();
+        let parsed = parsed?;
+
+        for &part in parts {
+            let start = Instant::now();
+            let (answer, alloc) = info_span!("part", %part).in_scope(|| {
+                alloc::measure(|| match part {
+                    Part::One => self.part_1(&parsed).into(),
+                    Part::Two => self.part_2(&parsed).into(),
+                })
+            });
+            report(PartResult {
+                part,
+                answer,
+                time: start.elapsed(),
+                alloc,
+            });
+        }
+
+        Ok((parse_time, parse_alloc))
+    }
+}
@@ -0,0 +1,395 @@
+//! Frames drawn by simulations and the backends that play them.
+//!
+//! A day that can be watched implements [`Visualize`] and draws its state into [`Frame`]s: grids
+//! of cells that each hold an index into a palette of [`Color`]s. It hands them to a
+//! [`Recorder`], which keeps every n-th one and passes it on to a [`Backend`], either a
+//! [`Terminal`] animation or a [`Gif`] file. The solvers themselves never draw, so runs without
+//! a visualization cost nothing.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::{ParseError, Part, Solution};
+
+/// One palette entry: how a cell looks in the terminal and in a GIF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub glyph: char,
+    pub rgb: [u8; 3],
+}
+
+impl Color {
+    pub const fn new(glyph: char, rgb: [u8; 3]) -> Self {
+        Color { glyph, rgb }
+    }
+}
+
+/// A grid of cells, each an index into `palette`. Row 0 is drawn at the top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    palette: &'static [Color],
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+}
+
+impl Frame {
+    /// A frame filled with the first palette entry, which is the background.
+    pub fn new(palette: &'static [Color], width: usize, height: usize) -> Self {
+        assert!(
+            !palette.is_empty() && palette.len() <= 256,
+            "a palette needs between 1 and 256 colors"
+        );
+        Frame {
+            palette,
+            width,
+            height,
+            cells: vec![0; width * height],
+        }
+    }
+
+    /// Sets the cell at `(x, y)` to palette entry `color`. Cells outside the frame are ignored,
+    /// so that callers can draw things that are partly out of view.
+    pub fn set(&mut self, x: usize, y: usize, color: u8) {
+        debug_assert!(usize::from(color) < self.palette.len());
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = color;
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn palette(&self) -> &'static [Color] {
+        self.palette
+    }
+
+    /// The palette indices of each row, from the top.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+}
+
+/// Somewhere to play frames. All frames of one animation share a size and palette.
+pub trait Backend {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()>;
+
+    /// Called once after the last frame.
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// Plays frames as an animation in an ANSI terminal, redrawing in place at `fps` frames per
+/// second. Frames larger than the terminal scroll, so big grids are better made into a [`Gif`].
+pub struct Terminal<W: Write = io::Stdout> {
+    out: W,
+    delay: Duration,
+    started: bool,
+}
+
+impl Terminal {
+    pub fn new(fps: u32) -> Self {
+        Terminal::with_writer(io::stdout(), fps)
+    }
+}
+
+impl<W: Write> Terminal<W> {
+    pub fn with_writer(out: W, fps: u32) -> Self {
+        Terminal {
+            out,
+            delay: Duration::from_secs(1) / fps.max(1),
+            started: false,
+        }
+    }
+}
+
+impl<W: Write> Backend for Terminal<W> {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        // Clear the screen and hide the cursor once, then draw each frame over the last one.
+        let mut text = String::from(if self.started { "" } else { "\x1b[2J\x1b[?25l" });
+        text.push_str("\x1b[H");
+        self.started = true;
+        for row in frame.rows() {
+            let mut current = None;
+            for &cell in row {
+                let color = frame.palette[usize::from(cell)];
+                if current != Some(color.rgb) {
+                    let [r, g, b] = color.rgb;
+                    text.push_str(&format!("\x1b[38;2;{r};{g};{b}m"));
+                    current = Some(color.rgb);
+                }
+                text.push(color.glyph);
+            }
+            text.push_str("\x1b[0m\n");
+        }
+        self.out.write_all(text.as_bytes())?;
+        self.out.flush()?;
+        thread::sleep(self.delay);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.write_all(b"\x1b[0m\x1b[?25h")?;
+        self.out.flush()
+    }
+}
+
+/// Encodes frames into a looping animated GIF, drawing each cell as a `scale` pixel square.
+pub struct Gif<W: Write> {
+    out: Option<W>,
+    encoder: Option<gif::Encoder<W>>,
+    /// Hundredths of a second per frame, which is as fine as GIF timing goes.
+    delay: u16,
+    scale: usize,
+}
+
+impl Gif<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>, fps: u32, scale: usize) -> io::Result<Self> {
+        Ok(Gif::new(BufWriter::new(File::create(path)?), fps, scale))
+    }
+}
+
+impl<W: Write> Gif<W> {
+    pub fn new(out: W, fps: u32, scale: usize) -> Self {
+        Gif {
+            out: Some(out),
+            encoder: None,
+            delay: (100 / fps.max(1)).max(1) as u16,
+            scale: scale.max(1),
+        }
+    }
+}
+
+impl<W: Write> Backend for Gif<W> {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        let width = frame.width * self.scale;
+        let height = frame.height * self.scale;
+        let too_large = || io::Error::other(format!("{width}x{height} is too large for a GIF"));
+        let width = u16::try_from(width).map_err(|_| too_large())?;
+        let height = u16::try_from(height).map_err(|_| too_large())?;
+
+        // The screen size and palette come from the first frame.
+        let encoder = match &mut self.encoder {
+            Some(encoder) => encoder,
+            None => {
+                let Some(out) = self.out.take() else {
+                    return Err(io::Error::other("the GIF is already finished"));
+                };
+                let palette: Vec<u8> = frame.palette.iter().flat_map(|color| color.rgb).collect();
+                let mut encoder =
+                    gif::Encoder::new(out, width, height, &palette).map_err(io::Error::other)?;
+                encoder
+                    .set_repeat(gif::Repeat::Infinite)
+                    .map_err(io::Error::other)?;
+                self.encoder.insert(encoder)
+            }
+        };
+
+        let mut pixels = Vec::with_capacity(usize::from(width) * usize::from(height));
+        for row in frame.rows() {
+            let start = pixels.len();
+            for &cell in row {
+                pixels.extend(std::iter::repeat_n(cell, self.scale));
+            }
+            let end = pixels.len();
+            for _ in 1..self.scale {
+                pixels.extend_from_within(start..end);
+            }
+        }
+        let mut image = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+        image.delay = self.delay;
+        encoder.write_frame(&image).map_err(io::Error::other)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        match self.encoder.take() {
+            Some(encoder) => encoder.into_inner().map_err(io::Error::other)?.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Collects the frames of one run and forwards every `every`-th step to a backend.
+///
+/// Backend errors stop the recording, and the first one is returned by [`Recorder::finish`],
+/// so that simulations can keep drawing without checking each frame.
+pub struct Recorder {
+    backend: Box<dyn Backend>,
+    every: usize,
+    steps: usize,
+    frames: usize,
+    error: Option<io::Error>,
+}
+
+impl Recorder {
+    pub fn new(backend: Box<dyn Backend>, every: usize) -> Self {
+        Recorder {
+            backend,
+            every: every.max(1),
+            steps: 0,
+            frames: 0,
+            error: None,
+        }
+    }
+
+    /// Marks one step of the simulation, calling `draw` only when the step is kept.
+    pub fn step(&mut self, draw: impl FnOnce() -> Frame) {
+        let keep = self.steps.is_multiple_of(self.every);
+        self.steps += 1;
+        if keep {
+            self.frame(draw);
+        }
+    }
+
+    /// Records a frame regardless of `every`, such as the final state.
+    pub fn frame(&mut self, draw: impl FnOnce() -> Frame) {
+        if self.error.is_none() {
+            match self.backend.frame(&draw()) {
+                Ok(()) => self.frames += 1,
+                Err(error) => self.error = Some(error),
+            }
+        }
+    }
+
+    /// Finishes the backend and returns how many frames were recorded.
+    pub fn finish(mut self) -> io::Result<usize> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.backend.finish()?;
+        Ok(self.frames)
+    }
+}
+
+/// A [`Solution`] that can draw its simulation. It replays `part` on `parsed`, recording its
+/// steps, separately from solving so that the solvers stay free of drawing code.
+pub trait Visualize: Solution {
+    fn visualize(&self, parsed: &Self::Parsed<'_>, part: Part, recorder: &mut Recorder);
+}
+
+/// Object-safe view of a [`Visualize`], for the runner's registry.
+pub trait DynVisualize {
+    fn visualize(&self, input: &str, part: Part, recorder: &mut Recorder)
+        -> Result<(), ParseError>;
+}
+
+impl<S: Visualize> DynVisualize for S {
+    fn visualize(
+        &self,
+        input: &str,
+        part: Part,
+        recorder: &mut Recorder,
+    ) -> Result<(), ParseError> {
+        let parsed = self.parse(input)?;
+        Visualize::visualize(self, &parsed, part, recorder);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    const PALETTE: &[Color] = &[Color::new(' ', [0, 0, 0]), Color::new('#', [255, 255, 255])];
+
+    #[derive(Clone, Default)]
+    struct Frames(Rc<RefCell<Vec<Frame>>>);
+
+    impl Backend for Frames {
+        fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+            self.0.borrow_mut().push(frame.clone());
+            Ok(())
+        }
+
+        fn finish(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn frames_ignore_cells_out_of_view() {
+        let mut frame = Frame::new(PALETTE, 3, 2);
+        frame.set(2, 1, 1);
+        frame.set(3, 0, 1);
+        frame.set(0, 2, 1);
+
+        assert_eq!(frame.rows().collect::<Vec<_>>(), [[0, 0, 0], [0, 0, 1]]);
+        assert_eq!(frame.get(2, 1), Some(1));
+        assert_eq!(frame.get(3, 0), None);
+    }
+
+    #[test]
+    fn recorder_keeps_every_nth_step() {
+        let frames = Frames::default();
+        let mut recorder = Recorder::new(Box::new(frames.clone()), 3);
+        let mut drawn = Vec::new();
+        for step in 0..7 {
+            recorder.step(|| {
+                drawn.push(step);
+                Frame::new(PALETTE, 1, 1)
+            });
+        }
+        recorder.frame(|| Frame::new(PALETTE, 1, 1));
+
+        assert_eq!(drawn, [0, 3, 6]);
+        assert_eq!(recorder.finish().unwrap(), 4);
+        assert_eq!(frames.0.borrow().len(), 4);
+    }
+
+    #[test]
+    fn terminal_redraws_in_place() {
+        let mut terminal = Terminal::with_writer(Vec::new(), 1000);
+        let mut frame = Frame::new(PALETTE, 2, 1);
+        frame.set(1, 0, 1);
+        terminal.frame(&frame).unwrap();
+        terminal.frame(&frame).unwrap();
+        terminal.finish().unwrap();
+
+        let row = "\x1b[38;2;0;0;0m \x1b[38;2;255;255;255m#\x1b[0m\n";
+        assert_eq!(
+            String::from_utf8(terminal.out).unwrap(),
+            format!("\x1b[2J\x1b[?25l\x1b[H{row}\x1b[H{row}\x1b[0m\x1b[?25h")
+        );
+    }
+
+    #[test]
+    fn gif_scales_cells_into_pixels() {
+        let mut out = Vec::new();
+        let mut gif = Gif::new(&mut out, 10, 2);
+        let mut frame = Frame::new(PALETTE, 2, 1);
+        frame.set(0, 0, 1);
+        gif.frame(&frame).unwrap();
+        gif.frame(&Frame::new(PALETTE, 2, 1)).unwrap();
+        gif.finish().unwrap();
+        drop(gif);
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(out.as_slice()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (4, 2));
+        assert_eq!(
+            decoder.global_palette().unwrap()[..6],
+            [0, 0, 0, 255, 255, 255]
+        );
+        let first = decoder.read_next_frame().unwrap().unwrap();
+        assert_eq!(first.delay, 10);
+        assert_eq!(*first.buffer, [1, 1, 0, 0, 1, 1, 0, 0]);
+        let second = decoder.read_next_frame().unwrap().unwrap();
+        assert_eq!(*second.buffer, [0; 8]);
+        assert!(decoder.read_next_frame().unwrap().is_none());
+    }
+}
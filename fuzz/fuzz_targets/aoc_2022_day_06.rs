@@ -0,0 +1,6 @@
+#![no_main]
+
+use aoc_2022_day_06::Day06;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| aoc_fuzz::check(&Day06::default(), data));
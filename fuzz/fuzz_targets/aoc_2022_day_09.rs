@@ -0,0 +1,11 @@
+#![no_main]
+
+use aoc_2022_day_09::Day09;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    aoc_fuzz::check_if(&Day09::default(), data, |moves| {
+        let steps = moves.iter().map(|(_, distance)| distance);
+        steps.fold(0_usize, |total, distance| total.saturating_add(*distance)) <= 100_000
+    });
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use aoc_2022_day_11::Day11;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let day = Day11 { rounds: Some(100) };
+    aoc_fuzz::check(&day, data);
+});
@@ -0,0 +1,6 @@
+#![no_main]
+
+use aoc_2022_day_13::Day13;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| aoc_fuzz::check(&Day13::default(), data));
@@ -0,0 +1,12 @@
+#![no_main]
+
+use aoc_2022_day_14::Day14;
+use libfuzzer_sys::fuzz_target;
+
+// Parsing stores every point of every rock path and the sand piles up to the square of the
+// depth, so keep coordinates small.
+fuzz_target!(|data: &[u8]| {
+    if aoc_fuzz::numbers_at_most(data, 200) {
+        aoc_fuzz::check(&Day14::default(), data);
+    }
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use aoc_2022_day_15::Day15;
+use libfuzzer_sys::fuzz_target;
+
+// Part 1 stores every position a sensor covers in the row, so keep their ranges small.
+fuzz_target!(|data: &[u8]| {
+    let day = Day15 {
+        row_index: 10,
+        max_coord: 20,
+    };
+    if aoc_fuzz::numbers_at_most(data, 1000) {
+        aoc_fuzz::check(&day, data);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use aoc_2022_day_16::Day16;
+use libfuzzer_sys::fuzz_target;
+
+// The search tries every order of opening the valves, so keep the scans short.
+fuzz_target!(|data: &[u8]| {
+    if data.split(|&byte| byte == b'\n').count() <= 10 {
+        aoc_fuzz::check(&Day16, data);
+    }
+});
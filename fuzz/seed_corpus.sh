@@ -0,0 +1,11 @@
+#!/bin/sh
+# Copies every day's example inputs into the corpus of its fuzz target.
+set -e
+cd "$(dirname "$0")"
+for target in fuzz_targets/*.rs; do
+    name=$(basename "$target" .rs)
+    year=$(echo "$name" | cut -d_ -f2)
+    day=$(echo "$name" | cut -d_ -f4)
+    mkdir -p "corpus/$name"
+    cp "../$year/day_$day"/assets/example*.txt "corpus/$name/"
+done
@@ -0,0 +1,54 @@
+//! Fuzz targets for the days' parse and solve phases, one binary per day under
+//! `fuzz_targets/`.
+//!
+//! Each target feeds arbitrary bytes to [`check`], so a crash means some input made a day panic,
+//! overflow or hang instead of returning a [`ParseError`](common::ParseError). Run one with
+//! [cargo-fuzz] from this directory, after seeding its corpus with the day's examples:
+//!
+//! ```text
+//! ./seed_corpus.sh
+//! cargo +nightly fuzz run aoc_2022_day_05 -- -timeout=5
+//! ```
+//!
+//! Targets that solve with puzzle parameters, like day 15's row, use small values, and days
+//! whose parsing or solving time grows with the numbers in the input rather than its length
+//! skip inputs with large numbers, so that a valid input is handled well within the timeout.
+//!
+//! [cargo-fuzz]: https://github.com/rust-fuzz/cargo-fuzz
+
+use common::Solution;
+
+/// Parses `data` and, when it is a valid input, solves both parts. Inputs that are not UTF-8
+/// are skipped, since the runner would never hand them to a day.
+pub fn check<S: Solution>(solution: &S, data: &[u8]) {
+    check_if(solution, data, |_| true);
+}
+
+/// Like [`check`], but only solves parsed inputs that `small_enough` accepts.
+pub fn check_if<S: Solution>(
+    solution: &S,
+    data: &[u8],
+    small_enough: impl Fn(&S::Parsed<'_>) -> bool,
+) {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(parsed) = solution.parse(input) else {
+        return;
+    };
+    if small_enough(&parsed) {
+        solution.part_1(&parsed);
+        solution.part_2(&parsed);
+    }
+}
+
+/// Whether every number written in `data` is at most `limit`.
+pub fn numbers_at_most(data: &[u8], limit: u64) -> bool {
+    data.split(|byte| !byte.is_ascii_digit()).all(|digits| {
+        digits.is_empty()
+            || std::str::from_utf8(digits)
+                .ok()
+                .and_then(|digits| digits.parse::<u64>().ok())
+                .is_some_and(|number| number <= limit)
+    })
+}